@@ -0,0 +1,137 @@
+use std::{collections::HashSet, fs::File, io::BufWriter, path::Path};
+
+use anyhow::{Context as _, bail};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::{
+    redact::is_redacted,
+    report::{Finding, FindingWithoutLine, open_report},
+};
+
+// How findings are matched against the baseline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BaselineMatch {
+    // gitleaks fingerprint, which contains the commit and the line.
+    #[default]
+    Fingerprint,
+    // Rule id, file and secret, to survive line shifts and rewritten commits.
+    Secret,
+}
+
+// `apply --format json` writes an array of confirmed findings, or an object with allowed findings
+// too with `--include-allowed`. Allowed findings are never reported, so only confirmed ones are used.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BaselineFile {
+    Findings(Vec<Finding>),
+    Result { confirmed: Vec<Finding> },
+}
+
+// Findings of a previous `apply --format json` output, to report only new findings.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    keys: HashSet<String>,
+    matching: BaselineMatch,
+}
+
+impl Baseline {
+    // Redacted secrets never match, so baselines written with `--redact` are rejected for `secret`.
+    pub fn read(path: &Path, matching: BaselineMatch) -> anyhow::Result<Self> {
+        let msg_f = || format!("Failed to read baseline: {}", path.display());
+        let findings = match serde_json::from_reader(open_report(path).with_context(msg_f)?)
+            .with_context(msg_f)?
+        {
+            BaselineFile::Findings(findings) => findings,
+            BaselineFile::Result { confirmed } => confirmed,
+        };
+        let redacted = findings.iter().any(|finding| is_redacted(&finding.secret));
+        let keys = findings
+            .iter()
+            .map(|finding| key(finding, matching))
+            .collect();
+        if redacted && matching == BaselineMatch::Secret {
+            bail!(
+                "Baseline has redacted secrets, which can't be matched by `secret`. Write it without `--redact`, e.g. with `--update-baseline`: {}",
+                path.display()
+            );
+        }
+        Ok(Self { keys, matching })
+    }
+
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.keys.contains(&key(finding, self.matching))
+    }
+}
+
+fn key(finding: &Finding, matching: BaselineMatch) -> String {
+    match matching {
+        BaselineMatch::Fingerprint => finding.fingerprint.clone(),
+        // NUL never appears in rule ids and paths, so keys are not ambiguous.
+        BaselineMatch::Secret => {
+            format!("{}\0{}\0{}", finding.rule_id, finding.file, finding.secret)
+        }
+    }
+}
+
+// Same as `apply --format json` output, so baselines can be created by either.
+pub fn write_baseline(path: &Path, findings: &[Finding]) -> anyhow::Result<()> {
+    let findings = findings
+        .iter()
+        .cloned()
+        .map(FindingWithoutLine::from)
+        .collect::<Vec<_>>();
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create baseline: {}", path.display()))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &findings)
+        .with_context(|| format!("Failed to write baseline: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{filter::FilterResult, report::test::build_empty_finding};
+
+    #[test]
+    fn test_baseline() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("baseline.json");
+        let finding = build_empty_finding();
+        write_baseline(&path, slice::from_ref(&finding))?;
+
+        let mut shifted = finding.clone();
+        shifted.start_line += 1;
+        shifted.fingerprint = "other-fingerprint".to_owned();
+
+        let baseline = Baseline::read(&path, BaselineMatch::Fingerprint)?;
+        assert!(baseline.contains(&finding));
+        assert!(!baseline.contains(&shifted));
+
+        let baseline = Baseline::read(&path, BaselineMatch::Secret)?;
+        assert!(baseline.contains(&shifted));
+        shifted.secret = "other-secret".to_owned();
+        assert!(!baseline.contains(&shifted));
+
+        // `apply --include-allowed` output.
+        let result = FilterResult {
+            repo_name: "repo".to_owned(),
+            metadata: None,
+            confirmed: vec![finding.clone()],
+            allowed: Vec::new(),
+        };
+        serde_json::to_writer(File::create(&path)?, &result)?;
+        let baseline = Baseline::read(&path, BaselineMatch::Fingerprint)?;
+        assert!(baseline.contains(&finding));
+
+        let mut redacted = finding;
+        redacted.secret = "REDACTED".to_owned();
+        write_baseline(&path, slice::from_ref(&redacted))?;
+        Baseline::read(&path, BaselineMatch::Fingerprint)?;
+        Baseline::read(&path, BaselineMatch::Secret).unwrap_err();
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ mod extract_allowlist;
 mod format;
 mod review;
 mod scan;
+mod schema;
 
 use std::{
     env::current_dir,
@@ -16,6 +17,8 @@ use std::{
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
 
+use crate::redact::Redaction;
+
 type CliResult = anyhow::Result<ExitCode>;
 
 const SUCCESS: CliResult = Ok(ExitCode::SUCCESS);
@@ -25,14 +28,15 @@ const FAILURE: CliResult = Ok(ExitCode::FAILURE);
 pub fn run() -> CliResult {
     let cli = Cli::parse();
     match cli.command {
-        Commands::Apply(args) => apply::apply(args),
+        Commands::Apply(args) => apply::apply(args, cli.redact),
         Commands::CleanupAllowlist(args) => cleanup_allowlist::cleanup_allowlist(args),
         Commands::CleanupRule(args) => cleanup_rule::cleanup_rule(args),
-        Commands::Diff(args) => diff::diff(args),
+        Commands::Diff(args) => diff::diff(args, cli.redact),
         Commands::ExtractAllowlist(args) => extract_allowlist::extract_allowlist(args),
         Commands::Format(args) => format::format(args),
-        Commands::Review(args) => review::review(args),
+        Commands::Review(args) => review::review(args, cli.redact),
         Commands::Scan(args) => scan::scan(args),
+        Commands::Schema(args) => schema::schema(args),
     }
 }
 
@@ -41,6 +45,11 @@ pub fn run() -> CliResult {
 struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// Redact secrets in outputs of `apply`, `review` and `diff`, including `Match`, `Line`,
+    /// `Message` and unknown fields. `full`, `keep:<N>` to keep the first and the last N characters,
+    /// or `hash` for a SHA-256 prefix, HMAC-SHA256 with `--secret-hash-key`.
+    #[arg(long, env, global = true, default_value = "none")]
+    pub redact: Redaction,
 }
 
 #[derive(Debug, Subcommand)]
@@ -53,6 +62,7 @@ enum Commands {
     Format(format::FormatArgs),
     Review(review::ReviewArgs),
     Scan(scan::ScanArgs),
+    Schema(schema::SchemaArgs),
 }
 
 pub(in crate::cli) fn resolve_root(root: Option<PathBuf>) -> anyhow::Result<PathBuf> {
@@ -1,36 +1,110 @@
+mod github;
+
 use std::{
-    fs::File,
+    fs::{File, OpenOptions, read_to_string},
     io::{Write, stdout},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use anyhow::{Context as _, bail};
+use anyhow::Context as _;
 use clap::{Args, ValueEnum};
 
 use crate::{
-    cli::{CliResult, FAILURE, SUCCESS, resolve_path, resolve_root},
-    config::read_allowlists,
-    filter::FindingFilter,
-    report::{FindingWithoutLine, Report, read_report},
-    sarif::to_sarif,
+    baseline::{Baseline, BaselineMatch, write_baseline},
+    cli::{
+        CliResult, FAILURE, SUCCESS,
+        apply::github::{BlobBase, write_annotations, write_summary},
+        resolve_path, resolve_root,
+    },
+    config::{Allowlist, read_config},
+    csv_output::{CsvColumn, FindingsCsv},
+    filter::{FilterResult, FilteredFinding, FindingFilter},
+    gitlab::to_gitlab,
+    gitleaks_config::{GitleaksConfig, Rule},
+    junit::JunitReport,
+    rdjson::{to_rdjson, write_rdjsonl},
+    redact::Redaction,
+    report::{
+        AllowedFinding, Finding, FindingWithoutLine, ReportFormat, STDIN_PATH, for_each_finding,
+        is_stdin, repo_name,
+    },
+    sarif::{SarifOptions, SecretHash, to_sarif},
+    severity::{RuleSeverity, Severities, Severity},
 };
 
 #[derive(Debug, Args)]
 pub struct ApplyArgs {
     #[arg(short, long, env)]
     config_path: PathBuf,
+    /// Path to the gitleaks report. Use `-` to read the report from stdin.
     #[arg(short, long, env)]
     report_path: PathBuf,
+    /// Repository name of the report. Defaults to the name in the report metadata or the report file
+    /// stem, required when reading the report from stdin.
+    #[arg(long, env, required_if_eq("report_path", STDIN_PATH))]
+    repo_name: Option<String>,
+    /// Format of the gitleaks report. Inferred from the report file extension if not specified, JSON
+    /// for stdin.
+    #[arg(long, env)]
+    input_format: Option<ReportFormat>,
     #[arg(long, env)]
     root: Option<PathBuf>,
-    /// SARIF for reviewdog. JSON format reports can be used as gitleaks baseline.
+    /// `rdjson` and `rdjsonl` for reviewdog, SARIF for other code scanning tools. JSON format reports
+    /// can be used as gitleaks baseline. `gitlab` is the
+    /// GitLab secret detection report for merge request security widgets. `junit` reports rules as
+    /// test suites and confirmed findings as failed test cases. `github-summary`
+    /// writes a Markdown summary to `--summary-path` in addition to `github` annotations. `csv` is for
+    /// spreadsheets.
     #[arg(short, long, env, default_value = "github")]
     format: Format,
     #[arg(short, long, env)]
     output: Option<PathBuf>,
-    /// Add extra guide message to GitHub format output.
+    /// Columns of `csv` format in order. Defaults to
+    /// `repo,status,allowlist,rule_id,file,start_line,end_line,secret,match,line,commit,fingerprint`.
+    #[arg(long, env, value_delimiter = ',')]
+    csv_columns: Vec<CsvColumn>,
+    /// File to append the Markdown summary of `github-summary` format to. Written to the output
+    /// after annotations if not specified.
+    #[arg(long, env = "GITHUB_STEP_SUMMARY")]
+    summary_path: Option<PathBuf>,
+    /// gitleaks config to describe rules in `sarif` format with their descriptions, keywords and
+    /// tags.
+    #[arg(long, env)]
+    gitleaks_config_path: Option<PathBuf>,
+    /// Key of HMAC-SHA256 for `secretHash/v1` fingerprints in `sarif` format and for
+    /// `--redact hash`, which are derived from secrets. With `--redact`, fingerprints are written
+    /// only with this key.
+    #[arg(long, env)]
+    secret_hash_key: Option<String>,
+    /// Add extra guide message to GitHub and SARIF format output, and to SARIF rule help. Used as
+    /// the rule URL in `rdjson` and `rdjsonl` formats.
     #[arg(short, long, env)]
     guide: Option<String>,
+    /// Severity of findings per rule in `<rule_id>=<severity>` format, overriding
+    /// `[extensions.severities]` of the config. Findings of other rules are `warning`. Used by
+    /// `sarif`, `rdjson`, `rdjsonl` and GitHub formats, where `info` is `notice`.
+    #[arg(long, env, value_delimiter = ',')]
+    rule_severity: Vec<RuleSeverity>,
+    /// Fail only if there are confirmed findings of this severity or higher.
+    #[arg(long, env, default_value = "info")]
+    fail_on: Severity,
+    /// Previous `apply --format json` output, with or without `--include-allowed`. Confirmed
+    /// findings in it are not reported, to see only new ones.
+    #[arg(long, env)]
+    baseline: Option<PathBuf>,
+    /// How findings are matched against the baseline. `secret` matches by rule id, file and secret,
+    /// which survives line shifts, and needs a baseline written without `--redact`.
+    #[arg(long, env, default_value = "fingerprint")]
+    baseline_match: BaselineMatch,
+    /// Write all confirmed findings to `--baseline`, creating or replacing it. Secrets are written
+    /// as is to match by `secret`.
+    #[arg(long, env, requires = "baseline")]
+    update_baseline: bool,
+    /// Include allowed findings in the output. `json` format writes an object with both confirmed
+    /// and allowed findings like `review --mode json`, `csv` format adds their rows, `sarif` format writes them as results
+    /// suppressed by allowlists and `junit` format writes them as skipped test cases.
+    #[arg(long, env)]
+    include_allowed: bool,
     /// Do not fail if there are confirmed findings. Fail on errors even if `no_fail` is true.
     #[arg(short, long, env)]
     no_fail: bool,
@@ -38,26 +112,142 @@ pub struct ApplyArgs {
 
 #[derive(Debug, Clone, ValueEnum)]
 enum Format {
+    Csv,
     Json,
     Github,
+    GithubSummary,
+    Gitlab,
+    Junit,
+    Rdjson,
+    Rdjsonl,
     Sarif,
 }
 
-pub fn apply(args: ApplyArgs) -> CliResult {
-    let root = resolve_root(args.root)?;
-    let allowlist_path = resolve_path(args.config_path, &root);
-    let allowlists = read_allowlists(&allowlist_path)?;
+#[allow(clippy::needless_pass_by_value)]
+pub fn apply(args: ApplyArgs, redaction: Redaction) -> CliResult {
+    let redaction = redaction.with_key(args.secret_hash_key.as_deref());
+    let root = resolve_root(args.root.clone())?;
+    let allowlist_path = resolve_path(args.config_path.clone(), &root);
+    let config = read_config(&allowlist_path)?;
+    let allowlists = config.allowlists;
     let filter = FindingFilter::new(&allowlists);
+    let severities = Severities::new(config.severities, args.rule_severity.clone());
 
-    let path = resolve_path(args.report_path, &root);
-    if path.extension().unwrap_or_default() != "json" {
-        bail!("JSON file extension expected: {}", path.display(),)
+    let path = if is_stdin(&args.report_path) {
+        args.report_path.clone()
+    } else {
+        resolve_path(args.report_path.clone(), &root)
+    };
+    let repo_name = match &args.repo_name {
+        Some(repo_name) => repo_name.clone(),
+        None => repo_name(&path)?,
+    };
+    let input_format = ReportFormat::resolve(args.input_format, &path)?;
+    // Allowed findings are not used unless requested, so drop them while reading the report.
+    let mut confirmed = Vec::new();
+    let mut allowed = Vec::new();
+    for_each_finding(&path, input_format, |finding| match filter.apply(finding) {
+        FilteredFinding::Confirmed(finding) => confirmed.push(finding),
+        FilteredFinding::Allowed(allowed_finding) if args.include_allowed => {
+            allowed.push(allowed_finding);
+        }
+        FilteredFinding::Allowed(_) => {}
+    })?;
+
+    if let Some(baseline_path) = &args.baseline {
+        let baseline_path = resolve_path(baseline_path.clone(), &root);
+        confirmed = apply_baseline(&args, &baseline_path, confirmed)?;
     }
-    let report: Report = read_report(&path)?;
-    let result = filter.apply_report(report);
 
     // Bind for later use.
-    let confirmed_count = result.confirmed.len();
+    let confirmed_count = confirmed.len();
+    let failing_count = confirmed
+        .iter()
+        .filter(|finding| severities.severity(finding) >= args.fail_on)
+        .count();
+    // SARIF redacts secrets by itself to compute fingerprints from the original ones.
+    let (confirmed, allowed) = if matches!(args.format, Format::Sarif) {
+        (confirmed, allowed)
+    } else {
+        (
+            redaction.redact_findings(confirmed),
+            redaction.redact_allowed_findings(allowed),
+        )
+    };
+    write_output(
+        &args,
+        &repo_name,
+        confirmed,
+        allowed,
+        &allowlists,
+        &severities,
+        &redaction,
+    )?;
+
+    if confirmed_count < 1 {
+        eprintln!("No finding are confirmed in {repo_name}.");
+        return SUCCESS;
+    }
+
+    eprintln!("{confirmed_count} findings are confirmed in {repo_name}.");
+    if failing_count < 1 {
+        eprintln!("No finding are {} or higher severity.", args.fail_on);
+        return SUCCESS;
+    }
+    if args.no_fail { SUCCESS } else { FAILURE }
+}
+
+// Updating the baseline accepts all current findings, so nothing is reported as new.
+fn apply_baseline(
+    args: &ApplyArgs,
+    baseline_path: &Path,
+    confirmed: Vec<Finding>,
+) -> anyhow::Result<Vec<Finding>> {
+    if args.update_baseline {
+        write_baseline(baseline_path, &confirmed)?;
+        eprintln!(
+            "Baseline is updated with {} findings: {}",
+            confirmed.len(),
+            baseline_path.display()
+        );
+        return Ok(Vec::new());
+    }
+    let baseline = Baseline::read(baseline_path, args.baseline_match)?;
+    let (known, new): (Vec<_>, Vec<_>) = confirmed
+        .into_iter()
+        .partition(|finding| baseline.contains(finding));
+    if !known.is_empty() {
+        eprintln!("{} findings are in the baseline.", known.len());
+    }
+    Ok(new)
+}
+
+fn read_gitleaks_rules(path: &Path) -> anyhow::Result<Vec<Rule>> {
+    let contents = read_to_string(path)
+        .with_context(|| format!("Failed to read gitleaks config: {}", path.display()))?;
+    let config = toml::from_str::<GitleaksConfig>(&contents)
+        .with_context(|| format!("Failed to parse gitleaks config: {}", path.display()))?;
+    Ok(config.rules.unwrap_or_default())
+}
+
+// `$GITHUB_STEP_SUMMARY` is shared by steps, so append to it.
+fn open_summary(path: &Path) -> anyhow::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open summary file: {}", path.display()))
+}
+
+fn write_output(
+    args: &ApplyArgs,
+    repo_name: &str,
+    confirmed: Vec<Finding>,
+    allowed: Vec<AllowedFinding>,
+    allowlists: &[Allowlist],
+    severities: &Severities,
+    redaction: &Redaction,
+) -> anyhow::Result<()> {
     let mut out: &mut dyn Write = match &args.output {
         Some(path) => &mut File::create(path)?,
         None => &mut stdout(),
@@ -70,48 +260,94 @@ pub fn apply(args: ApplyArgs) -> CliResult {
         format!("Failed to write to {out_description}, possibly piped command ends with an error")
     };
     match args.format {
+        // Same as `review --mode json` output, which has both lists.
+        Format::Json if args.include_allowed => {
+            let result = FilterResult {
+                repo_name: repo_name.to_owned(),
+                metadata: None,
+                confirmed,
+                allowed,
+            };
+            writeln!(out, "{}", serde_json::to_string_pretty(&result)?).with_context(msg_f)?;
+        }
         Format::Json => {
             // Omit `line` for compatibility with reports from non-patched gitleaks.
-            let confirmed = result
-                .confirmed
+            let confirmed = confirmed
                 .into_iter()
                 .map(FindingWithoutLine::from)
                 .collect::<Vec<_>>();
             writeln!(out, "{}", serde_json::to_string_pretty(&confirmed)?).with_context(msg_f)?;
         }
         Format::Sarif => {
-            let guide = args
-                .guide
-                .map_or_else(String::new, |guide| format!("\n\n{guide}"));
-            // SARIF doesn't contain `line` field, so pass original Finding-s.
-            let s = to_sarif(result.confirmed, &guide)?;
+            let gitleaks_rules = match &args.gitleaks_config_path {
+                Some(path) => read_gitleaks_rules(path)?,
+                None => Vec::new(),
+            };
+            // Unredacted findings, `to_sarif` redacts them after computing levels and fingerprints.
+            let s = to_sarif(
+                confirmed,
+                allowed,
+                &SarifOptions {
+                    allowlists,
+                    gitleaks_rules: &gitleaks_rules,
+                    severities,
+                    guide: args.guide.as_deref(),
+                    redaction,
+                    secret_hash: &SecretHash::new(redaction, args.secret_hash_key.as_deref()),
+                },
+            )?;
             writeln!(out, "{s}").with_context(msg_f)?;
         }
-        Format::Github => {
-            let title = "Secrets detected";
-            let guide = args
-                .guide
-                .map_or_else(String::new, |guide| format!(" {guide}"));
-            for finding in result.confirmed {
-                let file = finding.file;
-                let line = finding.start_line;
-                let end_line = finding.end_line;
-                let message =
-                    format!("`{}` is considered as secret value.{guide}", finding.secret,);
-                // Output this to file is not usefull but for config consistency.
-                writeln!(
-                    &mut out,
-                    "::warning file={file},line={line},endLine={end_line},title={title}::{message}"
-                )?;
+        Format::Csv => {
+            let mut csv = FindingsCsv::new(&mut out, &args.csv_columns);
+            for finding in &confirmed {
+                csv.add_confirmed(repo_name, finding);
             }
+            for allowed_finding in &allowed {
+                csv.add_allowed(repo_name, allowed_finding);
+            }
+            csv.finish().with_context(msg_f)?;
+        }
+        Format::Gitlab => {
+            let s = to_gitlab(confirmed)?;
+            writeln!(out, "{s}").with_context(msg_f)?;
+        }
+        Format::Rdjson => {
+            let s = to_rdjson(confirmed, severities, args.guide.as_deref())?;
+            writeln!(out, "{s}").with_context(msg_f)?;
+        }
+        Format::Rdjsonl => {
+            write_rdjsonl(confirmed, severities, args.guide.as_deref(), &mut out)
+                .with_context(msg_f)?;
+        }
+        Format::Junit => {
+            let mut report = JunitReport::default();
+            for finding in &confirmed {
+                report.add_confirmed(repo_name, finding);
+            }
+            for allowed_finding in &allowed {
+                report.add_allowed(repo_name, allowed_finding);
+            }
+            report.write(&mut out).with_context(msg_f)?;
+        }
+        Format::Github => {
+            write_annotations(&confirmed, args.guide.as_deref(), severities, &mut out)?;
+        }
+        Format::GithubSummary => {
+            write_annotations(&confirmed, args.guide.as_deref(), severities, &mut out)?;
+            let blob_base = BlobBase::from_env();
+            let mut summary_out: &mut dyn Write = match &args.summary_path {
+                Some(path) => &mut open_summary(path)?,
+                None => &mut out,
+            };
+            write_summary(
+                &confirmed,
+                repo_name,
+                args.guide.as_deref(),
+                &blob_base,
+                &mut summary_out,
+            )?;
         }
     }
-
-    if confirmed_count < 1 {
-        eprintln!("No finding are confirmed.");
-        return SUCCESS;
-    }
-
-    eprintln!("{confirmed_count} findings are confirmed.");
-    if args.no_fail { SUCCESS } else { FAILURE }
+    Ok(())
 }
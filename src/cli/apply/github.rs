@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, env, io::Write};
+
+use serde_json::Value;
+use tabled::{builder::Builder, settings::Style};
+
+use crate::{
+    report::Finding,
+    severity::{Severities, Severity},
+};
+
+const TITLE: &str = "Secrets detected";
+
+// GitHub shows at most 10 annotations of each level per step.
+// https://docs.github.com/en/rest/checks/runs#update-a-check-run
+const ANNOTATION_LIMIT: usize = 10;
+
+const fn to_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "notice",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// Annotations beyond the limit are written as plain log lines, and the last annotation of the level
+// tells how many are not annotated.
+pub fn write_annotations(
+    confirmed: &[Finding],
+    guide: Option<&str>,
+    severities: &Severities,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let guide = guide.map_or_else(String::new, |guide| format!(" {guide}"));
+    let mut by_level = BTreeMap::<&str, Vec<&Finding>>::new();
+    for finding in confirmed {
+        let level = to_level(severities.severity(finding));
+        by_level.entry(level).or_default().push(finding);
+    }
+
+    for (level, findings) in by_level {
+        let annotated = if findings.len() > ANNOTATION_LIMIT {
+            ANNOTATION_LIMIT - 1
+        } else {
+            findings.len()
+        };
+        let (annotated, overflowed) = findings.split_at(annotated);
+        for finding in annotated {
+            let message = format!("`{}` is considered as secret value.{guide}", finding.secret);
+            // Output this to file is not usefull but for config consistency.
+            writeln!(
+                out,
+                "::{level} {}::{}",
+                annotation_properties(finding),
+                escape_data(&message)
+            )?;
+        }
+        if overflowed.is_empty() {
+            continue;
+        }
+        // Escaped as annotations, so that values can't start workflow commands on new lines.
+        for finding in overflowed {
+            let message = format!(
+                "{}:{}: `{}` is considered as secret value.",
+                finding.file, finding.start_line, finding.secret
+            );
+            writeln!(out, "{level}: {}", escape_data(&message))?;
+        }
+        let message = format!(
+            "{} more findings are not annotated because of GitHub's annotation limit, see the step log.{guide}",
+            overflowed.len()
+        );
+        writeln!(
+            out,
+            "::{level} title={}::{}",
+            escape_property(TITLE),
+            escape_data(&message)
+        )?;
+    }
+    Ok(())
+}
+
+// GitHub uses columns only for single line annotations.
+fn annotation_properties(finding: &Finding) -> String {
+    let columns = if finding.start_line == finding.end_line && finding.start_column > 0 {
+        format!(
+            ",col={},endColumn={}",
+            finding.start_column, finding.end_column
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "file={},line={},endLine={}{columns},title={}",
+        escape_property(&finding.file),
+        finding.start_line,
+        finding.end_line,
+        escape_property(TITLE)
+    )
+}
+
+// Same as `escapeData` and `escapeProperty` of @actions/core.
+// https://github.com/actions/toolkit/blob/%40actions/core%401.11.1/packages/core/src/command.ts
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+// Base of links to files on GitHub, from the default environment variables of GitHub Actions.
+#[derive(Debug, Default)]
+pub struct BlobBase {
+    server_url: Option<String>,
+    repository: Option<String>,
+    sha: Option<String>,
+}
+
+impl BlobBase {
+    pub fn from_env() -> Self {
+        Self {
+            server_url: env::var("GITHUB_SERVER_URL").ok(),
+            repository: env::var("GITHUB_REPOSITORY").ok(),
+            sha: env::var("GITHUB_SHA").ok(),
+        }
+    }
+
+    // gitleaks reports may have `Link` already. Otherwise link to the commit of the finding, which
+    // surely has the file, or to the checked out commit for `gitleaks dir` reports.
+    fn link(&self, finding: &Finding) -> Option<String> {
+        if let Some(link) = finding.extra.get("Link").and_then(Value::as_str)
+            && !link.is_empty()
+        {
+            return Some(link.to_owned());
+        }
+        let server_url = self.server_url.as_deref()?;
+        let repository = self.repository.as_deref()?;
+        let commit = if finding.commit.is_empty() {
+            self.sha.as_deref()?
+        } else {
+            &finding.commit
+        };
+        let lines = if finding.start_line == finding.end_line {
+            format!("L{}", finding.start_line)
+        } else {
+            format!("L{}-L{}", finding.start_line, finding.end_line)
+        };
+        Some(format!(
+            "{server_url}/{repository}/blob/{commit}/{}#{lines}",
+            finding.file
+        ))
+    }
+}
+
+// Markdown for `$GITHUB_STEP_SUMMARY`.
+pub fn write_summary(
+    confirmed: &[Finding],
+    repo_name: &str,
+    guide: Option<&str>,
+    blob_base: &BlobBase,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    writeln!(out, "## {TITLE} in {}\n", escape_cell(repo_name))?;
+    if confirmed.is_empty() {
+        writeln!(out, "No finding are confirmed.")?;
+        return Ok(());
+    }
+    writeln!(out, "{} findings are confirmed.\n", confirmed.len())?;
+
+    let mut by_rule_id = BTreeMap::<&str, usize>::new();
+    for finding in confirmed {
+        *by_rule_id.entry(&finding.rule_id).or_default() += 1;
+    }
+    let mut builder = Builder::default();
+    builder.push_record(["rule_id", "confirmed"]);
+    for (rule_id, count) in by_rule_id {
+        builder.push_record([escape_cell(rule_id), count.to_string()]);
+    }
+    writeln!(out, "### Confirmed findings per rule\n")?;
+    writeln!(out, "{}\n", builder.build().with(Style::markdown()))?;
+
+    let mut builder = Builder::default();
+    builder.push_record(["rule_id", "file", "line", "secret"]);
+    for finding in confirmed {
+        let file = escape_cell(&finding.file);
+        let file = blob_base
+            .link(finding)
+            .map_or_else(|| file.clone(), |link| format!("[{file}]({link})"));
+        builder.push_record([
+            escape_cell(&finding.rule_id),
+            file,
+            finding.start_line.to_string(),
+            escape_cell(&finding.secret_in_length(30)),
+        ]);
+    }
+    writeln!(out, "### Confirmed findings\n")?;
+    writeln!(out, "{}", builder.build().with(Style::markdown()))?;
+
+    if let Some(guide) = guide {
+        writeln!(out, "\n{guide}")?;
+    }
+    Ok(())
+}
+
+// Table cells must be in one line and can't contain the column separator.
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
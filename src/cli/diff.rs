@@ -10,8 +10,10 @@ use tabled::{builder::Builder, settings::Style};
 
 use crate::{
     cli::{CliResult, SUCCESS, resolve_path, resolve_root},
+    csv_output::{CsvColumn, FindingsCsv},
     diff::{DiffResult, compute_diff},
     filter::FilterResult,
+    redact::Redaction,
 };
 
 #[derive(Debug, Args)]
@@ -26,16 +28,25 @@ pub struct DiffArgs {
     output: Option<PathBuf>,
     #[arg(short, long, env, default_value = "markdown")]
     format: Format,
+    /// Columns of `csv` format in order. Defaults to
+    /// `repo,status,allowlist,rule_id,file,start_line,end_line,secret,match,line,commit,fingerprint`.
+    #[arg(long, env, value_delimiter = ',')]
+    csv_columns: Vec<CsvColumn>,
     #[arg(long, env, default_value = "120")]
     file_length: usize,
     #[arg(long, env, default_value = "30")]
     secret_length: usize,
     #[arg(long, env, default_value = "80")]
     line_length: usize,
+    /// Key of HMAC-SHA256 for `--redact hash`, so that hashes of guessable secrets can't be
+    /// brute-forced.
+    #[arg(long, env)]
+    secret_hash_key: Option<String>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
 enum Format {
+    Csv,
     Json,
     Markdown,
 }
@@ -47,7 +58,8 @@ struct PathInfo<'path> {
 }
 
 #[allow(clippy::needless_pass_by_value)]
-pub fn diff(args: DiffArgs) -> CliResult {
+pub fn diff(args: DiffArgs, redaction: Redaction) -> CliResult {
+    let redaction = redaction.with_key(args.secret_hash_key.as_deref());
     let root = resolve_root(args.root.clone())?;
     let before_path = resolve_path(args.before.clone(), &root);
     let before_contents = read_to_string(&before_path)
@@ -61,7 +73,14 @@ pub fn diff(args: DiffArgs) -> CliResult {
     let afters: Vec<FilterResult> = serde_json::from_str(&after_contents)
         .with_context(|| format!("Failed to parse JSON: {}", after_path.display()))?;
 
-    let diffs = compute_diff(befores, afters);
+    let diffs = compute_diff(befores, afters)
+        .into_iter()
+        .map(|mut diff| {
+            diff.confirmed = redaction.redact_findings(diff.confirmed);
+            diff.allowed = redaction.redact_allowed_findings(diff.allowed);
+            diff
+        })
+        .collect::<Vec<_>>();
     let mut out: &mut dyn Write = match args.output.clone() {
         Some(path) => &mut File::create(path)?,
         None => &mut stdout(),
@@ -75,6 +94,18 @@ pub fn diff(args: DiffArgs) -> CliResult {
             };
             print_diffs_md(diffs, &mut out, &path_info, &args)?;
         }
+        Format::Csv => {
+            let mut csv = FindingsCsv::new(&mut out, &args.csv_columns);
+            for diff in &diffs {
+                for allowed_finding in &diff.allowed {
+                    csv.add_allowed(&diff.repo_name, allowed_finding);
+                }
+                for finding in &diff.confirmed {
+                    csv.add_confirmed(&diff.repo_name, finding);
+                }
+            }
+            csv.finish()?;
+        }
         Format::Json => {
             serde_json::to_writer_pretty(&mut out, &diffs)?;
             writeln!(out)?;
@@ -4,11 +4,12 @@ use std::{
     path::PathBuf,
 };
 
+use anyhow::Context as _;
 use clap::Args;
 
 use crate::{
     cli::{CliResult, SUCCESS},
-    config::ConfigRoot,
+    config::ConfigFormat,
 };
 
 #[derive(Debug, Args)]
@@ -17,16 +18,37 @@ pub struct FormatArgs {
     source: PathBuf,
     #[arg(short, long, env)]
     output: Option<PathBuf>,
+    /// Format of the source file. Inferred from the source file extension if not specified.
+    #[arg(long, env)]
+    input_format: Option<ConfigFormat>,
+    /// Format of the output. Inferred from the output file extension if not specified, or same as the
+    /// input format when writing to stdout.
+    #[arg(long, env)]
+    output_format: Option<ConfigFormat>,
 }
 
 pub fn format(args: FormatArgs) -> CliResult {
-    let contents = read_to_string(args.source)?;
-    let config: ConfigRoot = toml::from_str(&contents)?;
+    let input_format = args
+        .input_format
+        .unwrap_or_else(|| ConfigFormat::from_path(&args.source));
+    let output_format = args.output_format.unwrap_or_else(|| {
+        args.output
+            .as_ref()
+            .map_or(input_format, |path| ConfigFormat::from_path(path))
+    });
+
+    let contents = read_to_string(&args.source)?;
+    let config = input_format.parse(&contents).with_context(|| {
+        format!(
+            "Failed to parse {input_format} file {}",
+            args.source.display()
+        )
+    })?;
     let mut out: &mut dyn Write = match args.output {
         Some(path) => &mut File::create(path)?,
         None => &mut stdout(),
     };
-    write!(&mut out, "{}", toml::to_string(&config)?)?;
+    write!(&mut out, "{}", output_format.serialize(&config)?)?;
     writeln!(out)?;
     SUCCESS
 }
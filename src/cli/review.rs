@@ -8,14 +8,23 @@ use std::{
 
 use anyhow::{Result, bail};
 use clap::{Args, ValueEnum};
+use serde::{Serializer as _, ser::SerializeSeq};
+use serde_json::Serializer;
 use tabled::{builder::Builder, settings::Style};
 
 use crate::{
     cli::{CliResult, SUCCESS, resolve_path, resolve_root},
     collect_dir,
     config::read_allowlists,
-    filter::{FilterResult, FindingFilter},
-    report::{AllowedFinding, Report, read_report},
+    csv_output::{CsvColumn, FindingsCsv, escape_formula},
+    filter::{FilteredFinding, FindingFilter},
+    html::HtmlReport,
+    junit::JunitReport,
+    redact::Redaction,
+    report::{
+        AllowedFinding, Finding, ReportFormat, for_each_finding, metadata::is_metadata_path,
+        nested_repo_name, read_report, repo_name,
+    },
 };
 
 #[derive(Debug, Args)]
@@ -23,28 +32,53 @@ pub struct ReviewArgs {
     /// Path to the allowlist configuration file.
     #[arg(short, long, env)]
     config_path: PathBuf,
-    /// Directory path containing the scan reports.
+    /// Directory path containing the scan reports. Nested directories like `<org>/<repo>.json` are
+    /// read recursively and named `<org>/<repo>`.
     #[arg(short, long, env)]
     reports_dir_path: PathBuf,
+    /// Format of the scan reports. Inferred from each report file extension if not specified.
+    #[arg(long, env)]
+    input_format: Option<ReportFormat>,
     /// Root directory path for searching within other option paths.
     #[arg(long, env)]
     root: Option<PathBuf>,
     /// Review mode. `summary` for a findings summary, `allowed` for details on allowed findings,
-    /// `confirmed` for details on confirmed findings, `json` for both allowd and confirmed findings in JSON format.
+    /// `confirmed` for details on confirmed findings, `json` for both allowd and confirmed findings in JSON format,
+    /// `junit` for `JUnit` XML with rules as test suites and confirmed findings as failed test cases,
+    /// `html` for a single static HTML file with summary charts and a sortable, filterable table.
     #[arg(short, long, env, default_value = "summary")]
     mode: Mode,
+    /// Output format of `summary`, `allowed` and `confirmed` modes. `csv` writes full values without
+    /// truncation. `summary` mode CSV has `kind,name,total,allowed,confirmed` columns.
+    #[arg(long, env, default_value = "markdown")]
+    format: Format,
+    /// Columns of `csv` format in `allowed` and `confirmed` modes, in order. Defaults to
+    /// `repo,status,allowlist,rule_id,file,start_line,end_line,secret,match,line,commit,fingerprint`.
+    #[arg(long, env, value_delimiter = ',')]
+    csv_columns: Vec<CsvColumn>,
     /// Allowlists to include. If unspecified, all allowlists are included.
     #[arg(short, long, env, conflicts_with = "skip_allowlists")]
     select_allowlists: Vec<String>,
     /// Allowlists to exclude. If unspecified, no allowlists are excluded.
     #[arg(long, env)]
     skip_allowlists: Vec<String>,
+    /// Repositories to include. Org names like `<org>` select all repositories in the org. If
+    /// unspecified, all repositories are included.
+    #[arg(long, env, conflicts_with = "skip_repos")]
+    select_repos: Vec<String>,
+    /// Repositories to exclude, org names exclude all repositories in the org. If unspecified, no
+    /// repositories are excluded.
+    #[arg(long, env)]
+    skip_repos: Vec<String>,
     /// Detection rules to include. If unspecified, all rules are included.
     #[arg(long, env, conflicts_with = "skip_rules")]
     select_rules: Vec<String>,
     /// Detection rules to exclude. If unspecified, no rules are excluded.
     #[arg(long, env)]
     skip_rules: Vec<String>,
+    /// Include allowed findings as skipped test cases in `junit` mode.
+    #[arg(long, env)]
+    include_allowed: bool,
     /// Output column width for the `file` attribute.
     #[arg(long, env, default_value = "120")]
     file_length: usize,
@@ -57,6 +91,10 @@ pub struct ReviewArgs {
     /// Path to output results. Defaults to stdout if not specified.
     #[arg(short, long, env)]
     output: Option<PathBuf>,
+    /// Key of HMAC-SHA256 for `--redact hash`, so that hashes of guessable secrets can't be
+    /// brute-forced.
+    #[arg(long, env)]
+    secret_hash_key: Option<String>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -65,6 +103,22 @@ enum Mode {
     Allowed,
     Confirmed,
     Json,
+    Junit,
+    Html,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum Format {
+    Markdown,
+    Csv,
+}
+
+// Repository names are resolved while listing reports, so repositories are filtered before reading.
+#[derive(Debug)]
+struct ReportEntry {
+    path: PathBuf,
+    format: ReportFormat,
+    repo_name: String,
 }
 
 #[derive(Debug, Default)]
@@ -73,8 +127,49 @@ struct PerRuleResult {
     allowed: usize,
 }
 
+// Aggregates for `summary` mode, built while streaming findings so that findings themselves are
+// not kept in memory.
+#[derive(Debug, Default)]
+struct Summary {
+    repos: usize,
+    by_rule_id: BTreeMap<String, PerRuleResult>,
+    by_allowlist: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    fn add(&mut self, filtered: &FilteredFinding) {
+        match filtered {
+            FilteredFinding::Confirmed(finding) => {
+                let per_result = self.by_rule_id.entry(finding.rule_id.clone()).or_default();
+                per_result.confirmed += 1;
+            }
+            FilteredFinding::Allowed(allowed_finding) => {
+                let per_result = self
+                    .by_rule_id
+                    .entry(allowed_finding.finding.rule_id.clone())
+                    .or_default();
+                per_result.allowed += 1;
+                let count = self
+                    .by_allowlist
+                    .entry(allowed_finding.allow_rule_id.clone())
+                    .or_insert(0);
+                *count += 1;
+            }
+        }
+    }
+
+    fn confirmed_len(&self) -> usize {
+        self.by_rule_id.values().map(|r| r.confirmed).sum()
+    }
+
+    fn allowed_len(&self) -> usize {
+        self.by_rule_id.values().map(|r| r.allowed).sum()
+    }
+}
+
 #[allow(clippy::needless_pass_by_value)]
-pub fn review(args: ReviewArgs) -> CliResult {
+pub fn review(args: ReviewArgs, redaction: Redaction) -> CliResult {
+    let redaction = redaction.with_key(args.secret_hash_key.as_deref());
     let root = resolve_root(args.root.clone())?;
     let allowlist_path = resolve_path(args.config_path.clone(), &root);
     let allowlists = read_allowlists(&allowlist_path)?;
@@ -82,70 +177,156 @@ pub fn review(args: ReviewArgs) -> CliResult {
 
     let reports_path = resolve_path(args.reports_dir_path.clone(), &root);
     let reports = if reports_path.is_dir() {
-        read_reports(&reports_path)?
+        list_reports(&reports_path, &reports_path, args.input_format)?
     } else {
-        vec![read_report(&reports_path)?]
+        vec![ReportEntry {
+            format: ReportFormat::resolve(args.input_format, &reports_path)?,
+            repo_name: repo_name(&reports_path)?,
+            path: reports_path,
+        }]
     };
-    let results = reports
+    let reports = reports
         .into_iter()
-        .map(|report| filter.apply_report(report))
-        .collect::<Vec<FilterResult>>();
+        .filter(|report| is_repo_selected(&args, &report.repo_name))
+        .collect::<Vec<_>>();
+
+    if matches!(args.format, Format::Csv)
+        && matches!(args.mode, Mode::Json | Mode::Junit | Mode::Html)
+    {
+        bail!("`csv` format is not available in `json`, `junit` and `html` modes");
+    }
 
     let mut out: &mut dyn Write = match args.output.as_ref() {
         Some(path) => &mut File::create(path)?,
         None => &mut stdout(),
     };
     match args.mode {
-        Mode::Summary => print_summary(&results, &filter, &mut out)?,
-        Mode::Allowed => print_allowed_detail(results, &args, &mut out)?,
-        Mode::Confirmed => print_confirmed_detail(results, &args, &mut out)?,
-        Mode::Json => print_json(&results, &mut out)?,
+        Mode::Summary => print_summary(&reports, &filter, &args.format, &mut out)?,
+        Mode::Allowed => print_allowed_detail(&reports, &filter, &args, &redaction, &mut out)?,
+        Mode::Confirmed => {
+            print_confirmed_detail(&reports, &filter, &args, &redaction, &mut out)?;
+        }
+        Mode::Json => print_json(&reports, &filter, &redaction, &mut out)?,
+        Mode::Junit => print_junit(&reports, &filter, &args, &redaction, &mut out)?,
+        Mode::Html => print_html(&reports, &filter, &args, &redaction, &mut out)?,
     }
 
     SUCCESS
 }
 
-fn read_reports(reports_path: &Path) -> Result<Vec<Report>> {
+// Only list report files here, reports are read one by one later to limit memory usage.
+fn list_reports(
+    reports_path: &Path,
+    base: &Path,
+    format: Option<ReportFormat>,
+) -> Result<Vec<ReportEntry>> {
     collect_dir(reports_path, |mut acc, path| {
-        if path.extension().unwrap_or_default() == "json" {
-            let report = read_report(&path)?;
-            acc.push(report);
-        } else {
-            bail!(
-                "Unkown file extension found: expected=.json, actual={}",
-                path.display(),
-            );
+        if path.is_dir() {
+            acc.extend(list_reports(&path, base, format)?);
+            return Ok(acc);
+        }
+        if is_metadata_path(&path) {
+            return Ok(acc);
         }
+        acc.push(ReportEntry {
+            format: ReportFormat::resolve(format, &path)?,
+            repo_name: nested_repo_name(&path, base)?,
+            path,
+        });
         Ok(acc)
     })
 }
 
+// `org` matches `org/repo` so that a whole org can be selected or skipped.
+fn matches_repo(pattern: &str, repo_name: &str) -> bool {
+    repo_name == pattern
+        || repo_name
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn is_repo_selected(args: &ReviewArgs, repo_name: &str) -> bool {
+    let selected = args.select_repos.is_empty()
+        || args
+            .select_repos
+            .iter()
+            .any(|pattern| matches_repo(pattern, repo_name));
+    let skipped = args
+        .skip_repos
+        .iter()
+        .any(|pattern| matches_repo(pattern, repo_name));
+    selected && !skipped
+}
+
 fn print_summary(
-    results: &[FilterResult],
+    reports: &[ReportEntry],
     filter: &FindingFilter,
+    format: &Format,
     out: &mut dyn Write,
 ) -> anyhow::Result<()> {
+    let mut summary = Summary::default();
+    for report in reports {
+        summary.repos += 1;
+        for_each_finding(&report.path, report.format, |finding| {
+            summary.add(&filter.apply(finding));
+        })?;
+    }
+    if matches!(format, Format::Csv) {
+        return print_summary_csv(&summary, out);
+    }
+
     writeln!(out, "## Summary")?;
-    print_overview_summary(results, filter, out)?;
+    print_overview_summary(&summary, filter, out)?;
     writeln!(out, "\n### Confirmed findings summary")?;
-    print_confirmed_summary(results, out)?;
+    print_confirmed_summary(summary.by_rule_id, out)?;
     writeln!(out, "\n### Allowed findings summary")?;
-    print_allowed_summary(results, out)?;
+    print_allowed_summary(summary.by_allowlist, out)?;
+    Ok(())
+}
+
+// All tables in one CSV, told apart by `kind`: `total`, `rule` or `allowlist`.
+fn print_summary_csv(summary: &Summary, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(["kind", "name", "total", "allowed", "confirmed"])?;
+    let confirmed_len = summary.confirmed_len();
+    let allowed_len = summary.allowed_len();
+    writer.write_record([
+        "total",
+        "",
+        &(confirmed_len + allowed_len).to_string(),
+        &allowed_len.to_string(),
+        &confirmed_len.to_string(),
+    ])?;
+    for (rule_id, per_result) in &summary.by_rule_id {
+        writer.write_record([
+            "rule",
+            &escape_formula(rule_id.clone()),
+            &(per_result.confirmed + per_result.allowed).to_string(),
+            &per_result.allowed.to_string(),
+            &per_result.confirmed.to_string(),
+        ])?;
+    }
+    for (allowlist_id, count) in &summary.by_allowlist {
+        let count = count.to_string();
+        let allowlist_id = escape_formula(allowlist_id.clone());
+        writer.write_record(["allowlist", &allowlist_id, &count, &count, "0"])?;
+    }
+    writer.flush()?;
     Ok(())
 }
 
 fn print_overview_summary(
-    results: &[FilterResult],
+    summary: &Summary,
     filter: &FindingFilter,
     out: &mut dyn Write,
 ) -> anyhow::Result<()> {
     let mut builder = Builder::default();
     builder.push_record(["item", "count"]);
-    builder.push_record(["target repositories", &results.len().to_string()]);
+    builder.push_record(["target repositories", &summary.repos.to_string()]);
     builder.push_record(["enabled allowlists", &filter.allowlists_size().to_string()]);
 
-    let confirmed_len = results.iter().map(|r| r.confirmed.len()).sum::<usize>();
-    let allowed_len = results.iter().map(|r| r.allowed.len()).sum::<usize>();
+    let confirmed_len = summary.confirmed_len();
+    let allowed_len = summary.allowed_len();
     builder.push_record(["total findings", &(confirmed_len + allowed_len).to_string()]);
     builder.push_record(["total allowed findings", &allowed_len.to_string()]);
     builder.push_record(["total confirmed findings", &confirmed_len.to_string()]);
@@ -154,25 +335,13 @@ fn print_overview_summary(
     Ok(())
 }
 
-fn print_confirmed_summary(results: &[FilterResult], out: &mut dyn Write) -> anyhow::Result<()> {
+fn print_confirmed_summary(
+    results_by_rule_id: BTreeMap<String, PerRuleResult>,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
     let mut builder = Builder::default();
     builder.push_record(["rule_id", "total", "allowed", "confirmed"]);
 
-    let results_by_rule_id: BTreeMap<String, PerRuleResult> =
-        results.iter().fold(BTreeMap::new(), |acc, result| {
-            let acc = result.confirmed.iter().fold(acc, |mut acc, finding| {
-                let per_result = acc.entry(finding.rule_id.clone()).or_default();
-                per_result.confirmed += 1;
-                acc
-            });
-            result.allowed.iter().fold(acc, |mut acc, allowed_finding| {
-                let per_result = acc
-                    .entry(allowed_finding.finding.rule_id.clone())
-                    .or_default();
-                per_result.allowed += 1;
-                acc
-            })
-        });
     let mut results_by_rule_id_sorted = results_by_rule_id
         .into_iter()
         .collect::<Vec<(String, PerRuleResult)>>();
@@ -190,18 +359,13 @@ fn print_confirmed_summary(results: &[FilterResult], out: &mut dyn Write) -> any
     Ok(())
 }
 
-fn print_allowed_summary(results: &[FilterResult], out: &mut dyn Write) -> anyhow::Result<()> {
+fn print_allowed_summary(
+    results_by_allowlist: BTreeMap<String, usize>,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
     let mut builder = Builder::default();
     builder.push_record(["allow_list", "allowed count"]);
 
-    let results_by_allowlist = results.iter().fold(BTreeMap::new(), |acc, result| {
-        result.allowed.iter().fold(acc, |mut acc, finding| {
-            let allowlist_id = finding.allow_rule_id.clone();
-            let count = acc.entry(allowlist_id).or_insert(0);
-            *count += 1;
-            acc
-        })
-    });
     let mut results_by_allowlist_sorted = results_by_allowlist
         .into_iter()
         .collect::<Vec<(String, usize)>>();
@@ -214,30 +378,110 @@ fn print_allowed_summary(results: &[FilterResult], out: &mut dyn Write) -> anyho
     Ok(())
 }
 
+// Findings of the selected rules and allowlists, passed one by one to `f` in a single pass per report.
+fn for_each_selected<F>(
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
+    args: &ReviewArgs,
+    redaction: &Redaction,
+    mut f: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(&str, FilteredFinding),
+{
+    for report in reports {
+        for_each_finding(&report.path, report.format, |finding| {
+            let filtered = redaction.redact_filtered(filter.apply(finding));
+            let skipped = match &filtered {
+                FilteredFinding::Confirmed(finding) => should_skip_rule(args, finding),
+                FilteredFinding::Allowed(allowed_finding) => {
+                    is_selected(args, allowed_finding) || should_skip(args, allowed_finding)
+                }
+            };
+            if !skipped {
+                f(&report.repo_name, filtered);
+            }
+        })?;
+    }
+    Ok(())
+}
+
+// Allowed findings of the selected allowlists, passed one by one to `f`.
+fn for_each_allowed<F>(
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
+    args: &ReviewArgs,
+    redaction: &Redaction,
+    mut f: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(&str, AllowedFinding),
+{
+    for_each_selected(reports, filter, args, redaction, |repo_name, filtered| {
+        if let FilteredFinding::Allowed(allowed_finding) = filtered {
+            f(repo_name, allowed_finding);
+        }
+    })
+}
+
+// Confirmed findings of the selected rules, passed one by one to `f`.
+fn for_each_confirmed<F>(
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
+    args: &ReviewArgs,
+    redaction: &Redaction,
+    mut f: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(&str, Finding),
+{
+    for_each_selected(reports, filter, args, redaction, |repo_name, filtered| {
+        if let FilteredFinding::Confirmed(finding) = filtered {
+            f(repo_name, finding);
+        }
+    })
+}
+
 fn print_allowed_detail(
-    results: Vec<FilterResult>,
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
     args: &ReviewArgs,
+    redaction: &Redaction,
     out: &mut dyn Write,
 ) -> anyhow::Result<()> {
+    if matches!(args.format, Format::Csv) {
+        let mut csv = FindingsCsv::new(out, &args.csv_columns);
+        for_each_allowed(
+            reports,
+            filter,
+            args,
+            redaction,
+            |repo_name, allowed_finding| {
+                csv.add_allowed(repo_name, &allowed_finding);
+            },
+        )?;
+        return csv.finish();
+    }
+
     let mut builder = Builder::default();
     builder.push_record(["repo", "allowlist", "rule_id", "file", "secret", "line"]);
-
-    for result in results {
-        for allowed_finding in result.allowed {
-            if is_selected(args, &allowed_finding) || should_skip(args, &allowed_finding) {
-                continue;
-            }
+    for_each_allowed(
+        reports,
+        filter,
+        args,
+        redaction,
+        |repo_name, allowed_finding| {
             let finding = allowed_finding.finding;
             builder.push_record([
-                &result.repo_name,
+                repo_name,
                 &allowed_finding.allow_rule_id,
                 &finding.rule_id,
                 &finding.file_in_length(args.file_length),
                 &finding.secret_in_length(args.secret_length),
                 &finding.line_in_length(args.line_length),
             ]);
-        }
-    }
+        },
+    )?;
 
     let title_base = "Allowed findings";
     let title = if !args.select_allowlists.is_empty() {
@@ -261,30 +505,31 @@ fn print_allowed_detail(
 }
 
 fn print_confirmed_detail(
-    results: Vec<FilterResult>,
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
     args: &ReviewArgs,
+    redaction: &Redaction,
     out: &mut dyn Write,
 ) -> anyhow::Result<()> {
+    if matches!(args.format, Format::Csv) {
+        let mut csv = FindingsCsv::new(out, &args.csv_columns);
+        for_each_confirmed(reports, filter, args, redaction, |repo_name, finding| {
+            csv.add_confirmed(repo_name, &finding);
+        })?;
+        return csv.finish();
+    }
+
     let mut builder = Builder::default();
     builder.push_record(["repo", "rule_id", "file", "secret", "line"]);
-
-    for result in results {
-        for finding in result.confirmed {
-            let is_selected =
-                !args.select_rules.is_empty() && !args.select_rules.contains(&finding.rule_id);
-            let should_skip = args.skip_rules.contains(&finding.rule_id);
-            if is_selected || should_skip {
-                continue;
-            }
-            builder.push_record([
-                &result.repo_name,
-                &finding.rule_id,
-                &finding.file_in_length(args.file_length),
-                &finding.secret_in_length(args.secret_length),
-                &finding.line_in_length(args.line_length),
-            ]);
-        }
-    }
+    for_each_confirmed(reports, filter, args, redaction, |repo_name, finding| {
+        builder.push_record([
+            repo_name,
+            &finding.rule_id,
+            &finding.file_in_length(args.file_length),
+            &finding.secret_in_length(args.secret_length),
+            &finding.line_in_length(args.line_length),
+        ]);
+    })?;
 
     let title_base = "Confirmed findings";
     let title = if !args.select_rules.is_empty() {
@@ -315,8 +560,77 @@ fn should_skip(args: &ReviewArgs, allowed_finding: &AllowedFinding) -> bool {
         .contains(&allowed_finding.allow_rule_id)
 }
 
-fn print_json(results: &[FilterResult], out: &mut dyn Write) -> anyhow::Result<()> {
-    let s = serde_json::to_string_pretty(&results)?;
-    writeln!(out, "{s}")?;
+fn should_skip_rule(args: &ReviewArgs, finding: &Finding) -> bool {
+    (!args.select_rules.is_empty() && !args.select_rules.contains(&finding.rule_id))
+        || args.skip_rules.contains(&finding.rule_id)
+}
+
+// Write results report by report, so only one report is in memory at a time.
+fn print_json(
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
+    redaction: &Redaction,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut serializer = Serializer::pretty(&mut *out);
+    let mut seq = serializer.serialize_seq(None)?;
+    for entry in reports {
+        let mut report = read_report(&entry.path, entry.format)?;
+        report.repo_name.clone_from(&entry.repo_name);
+        seq.serialize_element(&redaction.redact_result(filter.apply_report(report)))?;
+    }
+    SerializeSeq::end(seq)?;
+    writeln!(out)?;
     Ok(())
 }
+
+fn print_junit(
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
+    args: &ReviewArgs,
+    redaction: &Redaction,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut junit = JunitReport::default();
+    for_each_selected(
+        reports,
+        filter,
+        args,
+        redaction,
+        |repo_name, filtered| match filtered {
+            FilteredFinding::Confirmed(finding) => junit.add_confirmed(repo_name, &finding),
+            FilteredFinding::Allowed(allowed_finding) if args.include_allowed => {
+                junit.add_allowed(repo_name, &allowed_finding);
+            }
+            FilteredFinding::Allowed(_) => {}
+        },
+    )?;
+    junit.write(out)
+}
+
+// Both confirmed and allowed findings, the report has filters to tell them apart.
+fn print_html(
+    reports: &[ReportEntry],
+    filter: &FindingFilter,
+    args: &ReviewArgs,
+    redaction: &Redaction,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut html = HtmlReport::default();
+    for report in reports {
+        html.add_repo(&report.repo_name);
+    }
+    for_each_selected(
+        reports,
+        filter,
+        args,
+        redaction,
+        |repo_name, filtered| match filtered {
+            FilteredFinding::Confirmed(finding) => html.add_confirmed(repo_name, &finding),
+            FilteredFinding::Allowed(allowed_finding) => {
+                html.add_allowed(repo_name, &allowed_finding);
+            }
+        },
+    )?;
+    html.write(out)
+}
@@ -1,15 +1,23 @@
 use std::{
-    fs::{self, create_dir_all, read_dir},
-    path::PathBuf,
+    fs::{self, create_dir_all, read_dir, remove_file},
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
 use anyhow::{Context, bail};
+use chrono::Utc;
 use clap::Args;
 use rayon::{ThreadPoolBuilder, prelude::*};
+use sha2::{Digest as _, Sha256};
 use tempfile::tempdir;
 
-use crate::cli::{CliResult, SUCCESS, resolve_path, resolve_root};
+use crate::{
+    cli::{CliResult, SUCCESS, resolve_path, resolve_root},
+    report::{
+        Compression,
+        metadata::{ReportMetadata, write_metadata},
+    },
+};
 
 /// Scan repositories for secrets using gitleaks.
 #[derive(Debug, Args)]
@@ -26,9 +34,16 @@ pub struct ScanArgs {
     /// Path to gitleaks config file.
     #[arg(short, long, env)]
     config: PathBuf,
-    /// Path to save the scan reports. The scan reports are saved with `<repo_name>.json` file name format.
+    /// Path to save the scan reports. The scan reports are saved with `<repo_name>.json` file name format,
+    /// along with `<repo_name>.meta.json` metadata.
     #[arg(short, long, env)]
     output: PathBuf,
+    /// Compress the scan reports, saved as `<repo_name>.json.gz` or `<repo_name>.json.zst`.
+    #[arg(long, env)]
+    compress: Option<Compression>,
+    /// Organization of the repositories, recorded in the report metadata.
+    #[arg(long, env)]
+    org: Option<String>,
     /// Number of threads to use for parallel scanning.
     #[arg(long, env)]
     threads: Option<usize>,
@@ -50,6 +65,9 @@ pub fn scan(args: ScanArgs) -> CliResult {
     }
     create_dir_all(&output_path)?;
 
+    let gitleaks_version = command_output(Command::new(&gitleaks_path).arg("version"));
+    let config_hash = format!("{:x}", Sha256::digest(fs::read(&config_path)?));
+
     let template_dir = tempdir()?;
     let template_path = template_dir.path().join("jsonextra.json.tmpl");
     fs::write(&template_path, TEMPLATE)?;
@@ -89,11 +107,28 @@ pub fn scan(args: ScanArgs) -> CliResult {
             .arg(&source_path)
             .stdin(Stdio::null());
 
+        let metadata = ReportMetadata {
+            repo_name: directory_name.to_owned(),
+            org: args.org.clone(),
+            remote_url: git_output(&source_path, &["remote", "get-url", "origin"]),
+            head_commit: git_output(&source_path, &["rev-parse", "HEAD"]),
+            gitleaks_version: gitleaks_version.clone(),
+            config_hash: Some(config_hash.clone()),
+            scanned_at: Some(Utc::now()),
+        };
+
         println!("{directory_name}: start scanning");
         let output = command
             .output()
             .with_context(|| format!("Failed to run gitleaks: {command:?}"))?;
         if output.status.success() {
+            if let Some(compression) = args.compress {
+                let compressed_path =
+                    output_path.join(format!("{directory_name}.json.{}", compression.extension()));
+                compression.compress_file(&report_path, &compressed_path)?;
+                remove_file(&report_path)?;
+            }
+            write_metadata(&report_path, &metadata)?;
             println!("{directory_name}: successfully scanned");
             Ok(())
         } else {
@@ -108,3 +143,17 @@ pub fn scan(args: ScanArgs) -> CliResult {
 
     SUCCESS
 }
+
+// Metadata is best effort, e.g. repositories without `origin` remote are still scanned.
+fn git_output(source_path: &Path, args: &[&str]) -> Option<String> {
+    command_output(Command::new("git").arg("-C").arg(source_path).args(args))
+}
+
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command.stdin(Stdio::null()).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        .filter(|s| !s.is_empty())
+}
@@ -0,0 +1,33 @@
+use std::{
+    fs::File,
+    io::{Write, stdout},
+    path::PathBuf,
+};
+
+use anyhow::Context as _;
+use clap::Args;
+use schemars::schema_for;
+
+use crate::{
+    cli::{CliResult, SUCCESS},
+    config::ConfigRoot,
+};
+
+/// Print JSON Schema of the gls config format. Useful for editor validation, e.g. with taplo.
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    /// Path to output the schema. Defaults to stdout if not specified.
+    #[arg(short, long, env)]
+    output: Option<PathBuf>,
+}
+
+pub fn schema(args: SchemaArgs) -> CliResult {
+    let schema = schema_for!(ConfigRoot);
+    let s = serde_json::to_string_pretty(&schema).context("Failed to serialize JSON Schema")?;
+    let mut out: &mut dyn Write = match args.output {
+        Some(path) => &mut File::create(path)?,
+        None => &mut stdout(),
+    };
+    writeln!(&mut out, "{s}")?;
+    SUCCESS
+}
@@ -1,69 +1,421 @@
-use std::{collections::HashSet, fs::read_to_string, path::Path, str::FromStr};
+mod error;
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::read_to_string,
+    path::Path,
+    str::FromStr,
+};
 
 use anyhow::{Context as _, Result, bail};
+use clap::ValueEnum;
 use regex::Regex;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::IgnoredAny};
+use toml::{
+    Spanned,
+    de::{DeTable, ValueDeserializer},
+};
 
-use crate::{collect_dir, gitleaks_config::GitleaksAllowlist};
+use crate::{collect_dir, gitleaks_config::GitleaksAllowlist, severity::Severity};
+use error::{ConfigError, Location};
+
+// Allowlists and severities of all config files in a path.
+#[derive(Debug)]
+pub struct Config {
+    pub allowlists: Vec<Allowlist>,
+    pub severities: SeverityConfig,
+}
 
 // Allowlist is a list of allow (ignore) rule. Return a list of allowlist here.
 pub fn read_allowlists(path: &Path) -> Result<Vec<Allowlist>> {
-    let allowlists = if path.is_file() {
+    read_config(path).map(|config| config.allowlists)
+}
+
+// All errors in the given path, of both allowlists and severities, are collected and reported at
+// once.
+pub fn read_config(path: &Path) -> Result<Config> {
+    let parsed = collect_config(path)?;
+    let mut errors = parsed.errors;
+    errors.append(&mut validate_duplication(&parsed.allowlists));
+    let (severities, mut severity_errors) = merge_severities(parsed.severities);
+    errors.append(&mut severity_errors);
+
+    if !errors.is_empty() {
+        let messages = errors
+            .iter()
+            .map(|e| format!("  {e}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "Found {} error(s) in config {}:\n{messages}",
+            errors.len(),
+            path.display()
+        );
+    }
+    Ok(Config {
+        allowlists: parsed
+            .allowlists
+            .into_iter()
+            .map(|(allowlist, _)| allowlist)
+            .collect(),
+        severities,
+    })
+}
+
+// Severities are merged across config files. The same rule id or tag with different severities is
+// an error, like duplicated allowlist ids.
+fn merge_severities(severities: Vec<LocatedSeverities>) -> (SeverityConfig, Vec<ConfigError>) {
+    let mut merged = SeverityConfig::default();
+    let mut errors = Vec::new();
+    for (config, location) in severities {
+        for (kind, merged, severities) in [
+            ("rule", &mut merged.rules, config.rules),
+            ("tag", &mut merged.tags, config.tags),
+        ] {
+            for (key, severity) in severities {
+                match merged.insert(key.clone(), severity) {
+                    Some(other) if other != severity => errors.push(ConfigError::new(
+                        location.clone(),
+                        None,
+                        format!("Conflicting severities of {kind} `{key}`: {other} and {severity}"),
+                    )),
+                    _ => {}
+                }
+            }
+        }
+    }
+    (merged, errors)
+}
+
+type LocatedAllowlist = (Allowlist, Location);
+type LocatedSeverities = (SeverityConfig, Location);
+
+// Parsed config files, invalid allowlists and severities are kept as errors.
+#[derive(Debug, Default)]
+pub struct ParsedConfig {
+    allowlists: Vec<LocatedAllowlist>,
+    severities: Vec<LocatedSeverities>,
+    errors: Vec<ConfigError>,
+}
+
+impl ParsedConfig {
+    fn from_error(error: ConfigError) -> Self {
+        Self {
+            errors: vec![error],
+            ..Self::default()
+        }
+    }
+
+    fn append(&mut self, other: &mut Self) {
+        self.allowlists.append(&mut other.allowlists);
+        self.severities.append(&mut other.severities);
+        self.errors.append(&mut other.errors);
+    }
+}
+
+fn collect_config(path: &Path) -> Result<ParsedConfig> {
+    if path.is_file() {
         let contents = read_to_string(path)
-            .with_context(|| format!("Failed to read allowlist from {}", path.display()))?;
-        let config: ConfigRoot = toml::from_str(&contents)
-            .with_context(|| format!("Failed to parse TOML file {}", path.display()))?;
-        config.extensions.allowlists
+            .with_context(|| format!("Failed to read config from {}", path.display()))?;
+        Ok(ConfigFormat::from_path(path).parse_config(path, &contents))
     } else if path.is_dir() {
-        collect_dir(path, |mut acc, path| {
-            let mut allowlists = read_allowlists(&path)?;
-            acc.append(&mut allowlists);
+        let parsed = collect_dir(path, |mut acc, path| {
+            acc.push(collect_config(&path)?);
             Ok(acc)
-        })?
+        })?;
+        Ok(parsed
+            .into_iter()
+            .fold(ParsedConfig::default(), |mut acc, mut parsed| {
+                acc.append(&mut parsed);
+                acc
+            }))
     } else {
         bail!("Invalid allowlist path: {}", path.display())
+    }
+}
+
+// Serialization format of gls config files. TOML is the canonical one, JSON and YAML are accepted
+// for allowlists generated by other tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    // Unknown extensions are treated as TOML to keep compatibility with existing config directories.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+
+    pub fn parse(self, contents: &str) -> Result<ConfigRoot> {
+        let config = match self {
+            Self::Toml => toml::from_str(contents)?,
+            Self::Json => serde_json::from_str(contents)?,
+            Self::Yaml => serde_yaml::from_str(contents)?,
+        };
+        Ok(config)
+    }
+
+    // Deserialize allowlists one by one to report all invalid allowlists in the file, not only the
+    // first one.
+    pub fn parse_config(self, path: &Path, contents: &str) -> ParsedConfig {
+        match self {
+            Self::Toml => parse_toml_config(path, contents),
+            Self::Json => {
+                match serde_json::from_str::<RawConfigRoot<serde_json::Value>>(contents) {
+                    Ok(root) => deserialize_config(
+                        path,
+                        root.extensions,
+                        |v| v.get("id").and_then(serde_json::Value::as_str),
+                        ConfigError::from_json,
+                    ),
+                    Err(e) => ParsedConfig::from_error(ConfigError::from_json(path, None, &e)),
+                }
+            }
+            Self::Yaml => {
+                match serde_yaml::from_str::<RawConfigRoot<serde_yaml::Value>>(contents) {
+                    Ok(root) => deserialize_config(
+                        path,
+                        root.extensions,
+                        |v| v.get("id").and_then(serde_yaml::Value::as_str),
+                        ConfigError::from_yaml,
+                    ),
+                    Err(e) => ParsedConfig::from_error(ConfigError::from_yaml(path, None, &e)),
+                }
+            }
+        }
+    }
+
+    pub fn serialize(self, config: &ConfigRoot) -> Result<String> {
+        let s = match self {
+            Self::Toml => toml::to_string(config)?,
+            Self::Json => serde_json::to_string_pretty(config)?,
+            Self::Yaml => serde_yaml::to_string(config)?,
+        };
+        Ok(s)
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Toml => "TOML",
+            Self::Json => "JSON",
+            Self::Yaml => "YAML",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// Same shape as `ConfigRoot` but leaves allowlists and severities as generic values, so each of
+// them can be deserialized separately.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfigRoot<A> {
+    extensions: RawExtensions<A>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawExtensions<A> {
+    allowlists: Vec<A>,
+    #[serde(default = "Option::default")]
+    severities: Option<A>,
+}
+
+// Severity errors are not of any allowlist, tell them apart by the message.
+fn severities_error(mut error: ConfigError) -> ConfigError {
+    error.message = format!("Invalid severities: {}", error.message);
+    error
+}
+
+fn parse_toml_config(path: &Path, contents: &str) -> ParsedConfig {
+    // Validate the document shape first, then walk the spanned document to keep positions.
+    let root = match toml::from_str::<RawConfigRoot<IgnoredAny>>(contents)
+        .and_then(|_| DeTable::parse(contents))
+    {
+        Ok(root) => root,
+        Err(e) => {
+            return ParsedConfig::from_error(ConfigError::from_toml(
+                path, contents, None, None, &e,
+            ));
+        }
     };
+    let extensions = root
+        .get_ref()
+        .get("extensions")
+        .and_then(|extensions| extensions.get_ref().as_table());
+    let items = extensions
+        .and_then(|extensions| extensions.get("allowlists"))
+        .and_then(|allowlists| allowlists.get_ref().as_array())
+        .map(AsRef::as_ref)
+        .unwrap_or_default();
 
-    validate_duplication(&allowlists)?;
-    Ok(allowlists)
+    let mut parsed = items
+        .iter()
+        .fold(ParsedConfig::default(), |mut parsed, item| {
+            let id = item.get_ref().as_table().and_then(|table| table.get("id"));
+            let span = id.map_or_else(|| item.span(), Spanned::span);
+            let id = id
+                .and_then(|id| id.get_ref().as_str())
+                .map(ToOwned::to_owned);
+            match Allowlist::deserialize(ValueDeserializer::from(item.clone())) {
+                Ok(allowlist) => {
+                    let location = Location::from_span(path, contents, &span);
+                    parsed.allowlists.push((allowlist, location));
+                }
+                Err(e) => {
+                    let error = ConfigError::from_toml(path, contents, id, Some(span), &e);
+                    parsed.errors.push(error);
+                }
+            }
+            parsed
+        });
+    if let Some(item) = extensions.and_then(|extensions| extensions.get("severities")) {
+        let span = item.span();
+        match SeverityConfig::deserialize(ValueDeserializer::from(item.clone())) {
+            Ok(severities) => parsed
+                .severities
+                .push((severities, Location::from_span(path, contents, &span))),
+            Err(e) => parsed.errors.push(severities_error(ConfigError::from_toml(
+                path,
+                contents,
+                None,
+                Some(span),
+                &e,
+            ))),
+        }
+    }
+    parsed
+}
+
+// JSON and YAML values don't have positions, so only the file path is recorded for allowlists.
+fn deserialize_config<'de, V, F, G>(
+    path: &Path,
+    extensions: RawExtensions<V>,
+    get_id: F,
+    to_error: G,
+) -> ParsedConfig
+where
+    V: Deserializer<'de>,
+    F: Fn(&V) -> Option<&str>,
+    G: Fn(&Path, Option<String>, &V::Error) -> ConfigError,
+{
+    let mut parsed =
+        extensions
+            .allowlists
+            .into_iter()
+            .fold(ParsedConfig::default(), |mut parsed, item| {
+                let id = get_id(&item).map(ToOwned::to_owned);
+                match Allowlist::deserialize(item) {
+                    Ok(allowlist) => parsed.allowlists.push((allowlist, Location::new(path))),
+                    Err(e) => parsed.errors.push(to_error(path, id, &e)),
+                }
+                parsed
+            });
+    if let Some(item) = extensions.severities {
+        match SeverityConfig::deserialize(item) {
+            Ok(severities) => parsed.severities.push((severities, Location::new(path))),
+            Err(e) => parsed
+                .errors
+                .push(severities_error(to_error(path, None, &e))),
+        }
+    }
+    parsed
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Root of a gls config file.
+#[derive(Deserialize, Serialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ConfigRoot {
+    /// gls specific extensions to the gitleaks config.
     pub extensions: Extensions,
 }
 
 impl ConfigRoot {
     pub const fn new(allowlists: Vec<Allowlist>) -> Self {
         Self {
-            extensions: Extensions { allowlists },
+            extensions: Extensions {
+                allowlists,
+                severities: SeverityConfig {
+                    rules: BTreeMap::new(),
+                    tags: BTreeMap::new(),
+                },
+            },
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// gls specific extensions to the gitleaks config.
+#[derive(Deserialize, Serialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Extensions {
+    /// Allowlists applied to gitleaks findings. Allowlist ids must be unique across all config files.
     pub allowlists: Vec<Allowlist>,
+    /// Severities of confirmed findings, used for output levels and `apply --fail-on`.
+    #[serde(default, skip_serializing_if = "SeverityConfig::is_empty")]
+    pub severities: SeverityConfig,
 }
 
+/// Severities by gitleaks rule id or tag. Findings matching neither are `warning`.
+#[derive(Deserialize, Serialize, Debug, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityConfig {
+    /// Severities by rule id, which take precedence over `tags`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<String, Severity>,
+    /// Severities by rule tag. The highest one applies when multiple tags match.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, Severity>,
+}
+
+impl SeverityConfig {
+    fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// A group of allow rules. A finding is allowed if any of the rules matches.
 // This is a group of allow rules so it's named Allowlist in gitleaks.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Allowlist {
     // `id` and `target_rule_ids` exist only in this tool.
+    /// Unique id of this allowlist, shown in review outputs.
     pub id: String,
     // This enables we have both multiple global allowlist items and rule local multiple allowlist items.
     // If this rule ids is empty, this allowlist is not mapped to all rules (global allowlist).
+    /// gitleaks rule ids this allowlist applies to. Empty means this allowlist applies to all rules.
     pub target_rule_ids: Vec<String>,
 
     // The following fields are same as gitleaks.
+    // Omit absent fields so JSON and YAML outputs don't contain `null`s, TOML omits them anyway.
+    /// Human readable description of this allowlist.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Regexes matched against the value selected by `regexTarget`.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub regexes: Option<Vec<RegexString>>,
+    /// Which part of a finding `regexes` are matched against. Defaults to `secret`.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub regex_target: Option<RegexTarget>,
+    /// Regexes matched against the file path of a finding.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub paths: Option<Vec<RegexString>>,
+    /// Commit SHAs to allow.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub commits: Option<Vec<String>>,
+    /// Allow a finding if its secret contains any of these words.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stopwords: Option<Vec<String>>,
 }
 
@@ -108,6 +460,23 @@ impl<'de> Deserialize<'de> for RegexString {
     }
 }
 
+impl JsonSchema for RegexString {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "RegexString".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "format": "regex",
+        })
+    }
+}
+
 impl Serialize for RegexString {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -117,12 +486,16 @@ impl Serialize for RegexString {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+/// Part of a finding which allowlist `regexes` are matched against.
+#[derive(Debug, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub enum RegexTarget {
+    /// The secret captured by the gitleaks rule.
     #[default]
     Secret,
+    /// The whole string matched by the gitleaks rule regex.
     Match,
+    /// The whole line containing the finding.
     Line,
 }
 
@@ -139,14 +512,20 @@ impl FromStr for RegexTarget {
     }
 }
 
-fn validate_duplication(allowlists: &[Allowlist]) -> Result<()> {
-    let mut ids = HashSet::new();
-    for allowlist in allowlists {
-        if !ids.insert(&allowlist.id) {
-            bail!("Duplicated allowlist id: {}", allowlist.id);
-        }
-    }
-    Ok(())
+fn validate_duplication(allowlists: &[LocatedAllowlist]) -> Vec<ConfigError> {
+    let mut ids = HashMap::new();
+    allowlists
+        .iter()
+        .filter_map(|(allowlist, location)| {
+            ids.insert(&allowlist.id, location).map(|first| {
+                ConfigError::new(
+                    location.clone(),
+                    Some(allowlist.id.clone()),
+                    format!("Duplicated allowlist id, first defined at {first}"),
+                )
+            })
+        })
+        .collect()
 }
 
 fn from_regex_strings(regex_strings: Option<Vec<String>>) -> Result<Option<Vec<RegexString>>> {
@@ -189,20 +568,180 @@ pub mod test {
 
 #[cfg(test)]
 mod tests {
+    use std::fs::write;
+
+    use tempfile::tempdir;
+
     use super::*;
 
+    fn locate(allowlist: Allowlist) -> LocatedAllowlist {
+        (allowlist, Location::new(Path::new("test.toml")))
+    }
+
     #[test]
     fn test_validate_duplication_fail() {
-        let allowlists = vec![test::build_empty_allowlist(), test::build_empty_allowlist()];
-        assert!(validate_duplication(&allowlists).is_err());
+        let allowlists = vec![
+            locate(test::build_empty_allowlist()),
+            locate(test::build_empty_allowlist()),
+        ];
+        let errors = validate_duplication(&allowlists);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "test.toml: allowlist `test-alowlist`: Duplicated allowlist id, first defined at test.toml"
+        );
     }
 
-    #[allow(clippy::assertions_on_result_states)]
     #[test]
     fn test_validate_duplication_ok() {
         let mut allowlist = test::build_empty_allowlist();
         "another-allowlist".clone_into(&mut allowlist.id);
-        let allowlists = vec![allowlist, test::build_empty_allowlist()];
-        assert!(validate_duplication(&allowlists).is_ok());
+        let allowlists = vec![locate(allowlist), locate(test::build_empty_allowlist())];
+        assert!(validate_duplication(&allowlists).is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_toml_errors() {
+        let contents = r#"[[extensions.allowlists]]
+id = "valid"
+targetRuleIds = []
+
+[[extensions.allowlists]]
+id = "invalid-regex"
+targetRuleIds = []
+regexes = ["ok", "("]
+
+[[extensions.allowlists]]
+id = "unknown-field"
+targetRuleIds = []
+unknown = true
+"#;
+        let path = Path::new("test.toml");
+        let ParsedConfig {
+            allowlists, errors, ..
+        } = ConfigFormat::Toml.parse_config(path, contents);
+        assert_eq!(allowlists.len(), 1);
+        let (allowlist, location) = allowlists.first().unwrap();
+        assert_eq!(allowlist.id, "valid");
+        assert_eq!(location.position, Some((2, 6)));
+
+        assert_eq!(errors.len(), 2);
+        let error = errors.first().unwrap();
+        assert_eq!(error.allowlist_id.as_deref(), Some("invalid-regex"));
+        assert_eq!(error.location.position, Some((8, 11)));
+        let error = errors.last().unwrap();
+        assert_eq!(error.allowlist_id.as_deref(), Some("unknown-field"));
+        assert_eq!(error.location.position, Some((13, 1)));
+    }
+
+    #[test]
+    fn test_parse_config_syntax_error() {
+        let path = Path::new("test");
+        let parsed = ConfigFormat::Toml.parse_config(path, "[extensions\n");
+        assert_eq!(
+            parsed.errors.first().unwrap().location.position,
+            Some((1, 12))
+        );
+
+        let parsed = ConfigFormat::Json.parse_config(path, "{\n  \"extensions\": \n}");
+        assert_eq!(
+            parsed.errors.first().unwrap().location.position,
+            Some((3, 1))
+        );
+    }
+
+    #[test]
+    fn test_parse_config_json_errors() {
+        let contents = r#"{"extensions": {"allowlists": [
+            {"id": "a", "targetRuleIds": [], "regexes": ["("]},
+            {"id": "b", "targetRuleIds": []}
+        ]}}"#;
+        let ParsedConfig {
+            allowlists, errors, ..
+        } = ConfigFormat::Json.parse_config(Path::new("a.json"), contents);
+        assert_eq!(allowlists.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().unwrap().allowlist_id.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_read_config_severities() -> Result<()> {
+        let dir = tempdir()?;
+        write(
+            dir.path().join("a.toml"),
+            "[extensions]\nallowlists = []\n[extensions.severities]\nrules = { r1 = \"error\" }\n",
+        )?;
+        write(
+            dir.path().join("b.json"),
+            r#"{"extensions": {"allowlists": [], "severities": {"tags": {"t1": "info"}}}}"#,
+        )?;
+        let severities = read_config(dir.path())?.severities;
+        assert_eq!(severities.rules.get("r1"), Some(&Severity::Error));
+        assert_eq!(severities.tags.get("t1"), Some(&Severity::Info));
+
+        write(
+            dir.path().join("c.yaml"),
+            "extensions:\n  allowlists: []\n  severities:\n    rules:\n      r1: warning\n",
+        )?;
+        let err = read_config(dir.path()).unwrap_err().to_string();
+        assert!(
+            err.contains("c.yaml: Conflicting severities of rule `r1`: error and warning"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_config_severity_errors() {
+        let contents =
+            "[extensions]\nallowlists = []\n\n[extensions.severities]\nrules.x = \"fatal\"\n";
+        let parsed = ConfigFormat::Toml.parse_config(Path::new("test.toml"), contents);
+        assert!(parsed.severities.is_empty());
+        let error = parsed.errors.first().unwrap();
+        assert_eq!(error.location.position, Some((5, 11)));
+        assert!(
+            error
+                .message
+                .starts_with("Invalid severities: unknown variant `fatal`"),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn test_config_format_from_path() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a.json")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a.yaml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a.yml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new("a")), ConfigFormat::Toml);
+    }
+
+    #[test]
+    fn test_config_format_parse() -> Result<()> {
+        let json = r#"{"extensions": {"allowlists": [{"id": "a", "targetRuleIds": [], "regexes": ["^x$"], "regexTarget": "line"}]}}"#;
+        let yaml = "extensions:\n  allowlists:\n  - id: a\n    targetRuleIds: []\n    regexes: ['^x$']\n    regexTarget: line\n";
+        for (format, contents) in [(ConfigFormat::Json, json), (ConfigFormat::Yaml, yaml)] {
+            let config = format.parse(contents)?;
+            let allowlist = config.extensions.allowlists.first().unwrap();
+            assert_eq!(allowlist.id, "a");
+            assert!(matches!(allowlist.regex_target, Some(RegexTarget::Line)));
+
+            let roundtrip = format.parse(&format.serialize(&config)?)?;
+            assert_eq!(roundtrip.extensions.allowlists.len(), 1);
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,163 @@
+use std::{
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Error as JsonError;
+use serde_yaml::Error as YamlError;
+use toml::de::Error as TomlError;
+
+// Where an allowlist or a config error is. `position` is 1-based (line, column) and is only
+// available when the underlying parser provides it.
+#[derive(Debug, Clone)]
+pub struct Location {
+    pub path: PathBuf,
+    pub position: Option<(usize, usize)>,
+}
+
+impl Location {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_owned(),
+            position: None,
+        }
+    }
+
+    pub fn from_span(path: &Path, contents: &str, span: &Range<usize>) -> Self {
+        Self {
+            path: path.to_owned(),
+            position: Some(position_of(contents, span.start)),
+        }
+    }
+
+    // Both serde_json and serde_yaml use 0 for unknown lines.
+    pub fn from_line_column(path: &Path, line: usize, column: usize) -> Self {
+        Self {
+            path: path.to_owned(),
+            position: (line > 0).then_some((line, column)),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((line, column)) = self.position {
+            write!(f, ":{line}:{column}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub location: Location,
+    pub allowlist_id: Option<String>,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub const fn new(location: Location, allowlist_id: Option<String>, message: String) -> Self {
+        Self {
+            location,
+            allowlist_id,
+            message,
+        }
+    }
+
+    // toml errors carry byte spans, convert them to line and column. `fallback` is used when the
+    // error has no span.
+    pub fn from_toml(
+        path: &Path,
+        contents: &str,
+        allowlist_id: Option<String>,
+        fallback: Option<Range<usize>>,
+        err: &TomlError,
+    ) -> Self {
+        let location = err.span().or(fallback).map_or_else(
+            || Location::new(path),
+            |span| Location::from_span(path, contents, &span),
+        );
+        Self::new(location, allowlist_id, err.message().to_owned())
+    }
+
+    pub fn from_json(path: &Path, allowlist_id: Option<String>, err: &JsonError) -> Self {
+        let location = Location::from_line_column(path, err.line(), err.column());
+        Self::new(
+            location,
+            allowlist_id,
+            strip_position(err.to_string(), err.line(), err.column()),
+        )
+    }
+
+    pub fn from_yaml(path: &Path, allowlist_id: Option<String>, err: &YamlError) -> Self {
+        let (line, column) = err
+            .location()
+            .map_or((0, 0), |location| (location.line(), location.column()));
+        let location = Location::from_line_column(path, line, column);
+        Self::new(
+            location,
+            allowlist_id,
+            strip_position(err.to_string(), line, column),
+        )
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.location)?;
+        if let Some(id) = &self.allowlist_id {
+            write!(f, "allowlist `{id}`: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+fn position_of(contents: &str, offset: usize) -> (usize, usize) {
+    let before = contents.get(..offset).unwrap_or(contents);
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+// serde_json and serde_yaml append the position to messages, drop it since it's in `Location`.
+fn strip_position(mut message: String, line: usize, column: usize) -> String {
+    let suffix = format!(" at line {line} column {column}");
+    if message.ends_with(&suffix) {
+        message.truncate(message.len() - suffix.len());
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_of() {
+        let contents = "a = 1\nbb = 2\n";
+        assert_eq!(position_of(contents, 0), (1, 1));
+        assert_eq!(position_of(contents, 6), (2, 1));
+        assert_eq!(position_of(contents, 11), (2, 6));
+    }
+
+    #[test]
+    fn test_display() {
+        let location = Location {
+            path: PathBuf::from("config.toml"),
+            position: Some((3, 5)),
+        };
+        let err = ConfigError::new(location, Some("a".to_owned()), "bad".to_owned());
+        assert_eq!(err.to_string(), "config.toml:3:5: allowlist `a`: bad");
+
+        let err = ConfigError::new(Location::new(Path::new("c.json")), None, "bad".to_owned());
+        assert_eq!(err.to_string(), "c.json: bad");
+    }
+}
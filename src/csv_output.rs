@@ -0,0 +1,215 @@
+use std::io::Write;
+
+use anyhow::Context as _;
+use clap::ValueEnum;
+use csv::Writer;
+
+use crate::report::{AllowedFinding, Finding};
+
+// Columns of CSV outputs, written in the given order. Named same as headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum CsvColumn {
+    Repo,
+    // `confirmed` or `allowed`.
+    Status,
+    Allowlist,
+    RuleId,
+    File,
+    StartLine,
+    EndLine,
+    StartColumn,
+    EndColumn,
+    Secret,
+    Match,
+    Line,
+    Commit,
+    Author,
+    Email,
+    Date,
+    Message,
+    Tags,
+    Fingerprint,
+    Scanner,
+}
+
+const DEFAULT_COLUMNS: &[CsvColumn] = &[
+    CsvColumn::Repo,
+    CsvColumn::Status,
+    CsvColumn::Allowlist,
+    CsvColumn::RuleId,
+    CsvColumn::File,
+    CsvColumn::StartLine,
+    CsvColumn::EndLine,
+    CsvColumn::Secret,
+    CsvColumn::Match,
+    CsvColumn::Line,
+    CsvColumn::Commit,
+    CsvColumn::Fingerprint,
+];
+
+impl CsvColumn {
+    const fn header(self) -> &'static str {
+        match self {
+            Self::Repo => "repo",
+            Self::Status => "status",
+            Self::Allowlist => "allowlist",
+            Self::RuleId => "rule_id",
+            Self::File => "file",
+            Self::StartLine => "start_line",
+            Self::EndLine => "end_line",
+            Self::StartColumn => "start_column",
+            Self::EndColumn => "end_column",
+            Self::Secret => "secret",
+            Self::Match => "match",
+            Self::Line => "line",
+            Self::Commit => "commit",
+            Self::Author => "author",
+            Self::Email => "email",
+            Self::Date => "date",
+            Self::Message => "message",
+            Self::Tags => "tags",
+            Self::Fingerprint => "fingerprint",
+            Self::Scanner => "scanner",
+        }
+    }
+
+    fn value(self, repo_name: &str, allowlist: Option<&str>, finding: &Finding) -> String {
+        match self {
+            Self::Repo => repo_name.to_owned(),
+            Self::Status => if allowlist.is_some() {
+                "allowed"
+            } else {
+                "confirmed"
+            }
+            .to_owned(),
+            Self::Allowlist => allowlist.unwrap_or_default().to_owned(),
+            Self::RuleId => finding.rule_id.clone(),
+            Self::File => finding.file.clone(),
+            Self::StartLine => finding.start_line.to_string(),
+            Self::EndLine => finding.end_line.to_string(),
+            Self::StartColumn => finding.start_column.to_string(),
+            Self::EndColumn => finding.end_column.to_string(),
+            Self::Secret => finding.secret.clone(),
+            Self::Match => finding.matched.clone(),
+            Self::Line => finding.line.clone().unwrap_or_default(),
+            Self::Commit => finding.commit.clone(),
+            Self::Author => finding.author.clone(),
+            Self::Email => finding.email.clone(),
+            Self::Date => finding.date.clone(),
+            Self::Message => finding.message.clone(),
+            Self::Tags => finding.tags.join(" "),
+            Self::Fingerprint => finding.fingerprint.clone(),
+            Self::Scanner => finding.scanner.clone().unwrap_or_default(),
+        }
+    }
+}
+
+// CSV of findings, one row per finding. Multi-line values like `Line` are quoted. Findings are
+// written in callbacks of streamed reports, so the first error is kept until `finish`.
+pub struct FindingsCsv<'out> {
+    writer: Writer<&'out mut dyn Write>,
+    columns: Vec<CsvColumn>,
+    error: Option<csv::Error>,
+}
+
+impl<'out> FindingsCsv<'out> {
+    // Empty columns mean the default columns.
+    pub fn new(out: &'out mut dyn Write, columns: &[CsvColumn]) -> Self {
+        let columns = if columns.is_empty() {
+            DEFAULT_COLUMNS.to_vec()
+        } else {
+            columns.to_vec()
+        };
+        let mut csv = Self {
+            writer: Writer::from_writer(out),
+            columns,
+            error: None,
+        };
+        let header = csv.columns.iter().map(|c| c.header()).collect::<Vec<_>>();
+        csv.write_record(header);
+        csv
+    }
+
+    pub fn add_confirmed(&mut self, repo_name: &str, finding: &Finding) {
+        self.add(repo_name, None, finding);
+    }
+
+    pub fn add_allowed(&mut self, repo_name: &str, allowed_finding: &AllowedFinding) {
+        self.add(
+            repo_name,
+            Some(&allowed_finding.allow_rule_id),
+            &allowed_finding.finding,
+        );
+    }
+
+    fn add(&mut self, repo_name: &str, allowlist: Option<&str>, finding: &Finding) {
+        let record = self
+            .columns
+            .iter()
+            .map(|c| escape_formula(c.value(repo_name, allowlist, finding)))
+            .collect::<Vec<_>>();
+        self.write_record(record);
+    }
+
+    fn write_record<I, T>(&mut self, record: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        if self.error.is_none()
+            && let Err(err) = self.writer.write_record(record)
+        {
+            self.error = Some(err);
+        }
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        if let Some(err) = self.error {
+            return Err(err).with_context(|| "Failed to write CSV");
+        }
+        self.writer.flush().with_context(|| "Failed to write CSV")
+    }
+}
+
+// Spreadsheets evaluate cells starting with these characters as formulas, and values come from
+// scanned repositories. Prefixed with `'` as recommended by OWASP.
+// https://owasp.org/www-community/attacks/CSV_Injection
+pub fn escape_formula(value: String) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test::{build_empty_allowed_finding, build_empty_finding};
+
+    #[test]
+    fn test_findings_csv() -> anyhow::Result<()> {
+        let mut out = Vec::new();
+        let mut csv = FindingsCsv::new(
+            &mut out,
+            &[
+                CsvColumn::Status,
+                CsvColumn::Allowlist,
+                CsvColumn::Line,
+                CsvColumn::Message,
+            ],
+        );
+        let mut finding = build_empty_finding();
+        finding.line = Some("multi\n\"line\"".to_owned());
+        finding.message = "=HYPERLINK(\"https://example.com\")".to_owned();
+        csv.add_confirmed("repo", &finding);
+        csv.add_allowed("repo", &build_empty_allowed_finding());
+        csv.finish()?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "status,allowlist,line,message\nconfirmed,,\"multi\n\"\"line\"\"\",\"'=HYPERLINK(\"\"https://example.com\"\")\"\nallowed,test-allow-rule,test-line: key = 'test-secret' # comment,test-message\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+document.addEventListener("DOMContentLoaded", () => {
+  const table = document.getElementById("findings");
+  const body = table.tBodies[0];
+  const rows = Array.from(body.rows);
+  const text = document.getElementById("filter");
+  const selects = Array.from(document.querySelectorAll("select[data-column]"));
+
+  const filter = () => {
+    const query = text.value.toLowerCase();
+    let shown = 0;
+    for (const row of rows) {
+      const visible =
+        selects.every((select) => !select.value || row.dataset[select.dataset.column] === select.value) &&
+        row.textContent.toLowerCase().includes(query);
+      row.hidden = !visible;
+      shown += visible ? 1 : 0;
+    }
+    document.getElementById("shown").textContent = shown;
+  };
+  text.addEventListener("input", filter);
+  selects.forEach((select) => select.addEventListener("change", filter));
+
+  const headers = Array.from(table.tHead.rows[0].cells);
+  headers.forEach((header, index) => {
+    header.addEventListener("click", () => {
+      const ascending = header.dataset.order !== "asc";
+      headers.forEach((h) => delete h.dataset.order);
+      header.dataset.order = ascending ? "asc" : "desc";
+      const numeric = header.dataset.type === "number";
+      const key = (row) => row.cells[index].textContent;
+      rows.sort((a, b) => {
+        const order = numeric ? Number(key(a)) - Number(key(b)) : key(a).localeCompare(key(b));
+        return ascending ? order : -order;
+      });
+      // Spreading all rows as arguments exceeds the call stack on large reviews.
+      const fragment = document.createDocumentFragment();
+      for (const row of rows) {
+        fragment.appendChild(row);
+      }
+      body.appendChild(fragment);
+    });
+  });
+});
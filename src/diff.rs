@@ -20,7 +20,7 @@ pub fn compute_diff(mut befores: Vec<FilterResult>, afters: Vec<FilterResult>) -
         .filter_map(|after| {
             let before = befores
                 .iter()
-                .position(|before| before.repo_name == after.repo_name)
+                .position(|before| before.is_same_repo(&after))
                 .map(|pos| befores.remove(pos));
             match before {
                 None => {
@@ -87,13 +87,17 @@ mod tests {
     use anyhow::Ok;
 
     use super::*;
-    use crate::report::test::{build_empty_allowed_finding, build_empty_finding};
+    use crate::report::{
+        metadata::ReportMetadata,
+        test::{build_empty_allowed_finding, build_empty_finding},
+    };
 
     type Result = anyhow::Result<()>;
 
     fn build_empty_filter_result(repo_name: &str) -> FilterResult {
         FilterResult {
             repo_name: repo_name.to_owned(),
+            metadata: None,
             confirmed: vec![],
             allowed: vec![],
         }
@@ -146,12 +150,14 @@ mod tests {
             build_empty_filter_result("repo1"),
             FilterResult {
                 repo_name: "repo2".to_owned(),
+                metadata: None,
                 confirmed: vec![build_empty_finding()],
                 allowed: vec![build_empty_allowed_finding()],
             },
         ];
         let afters = vec![FilterResult {
             repo_name: "repo2".to_owned(),
+            metadata: None,
             confirmed: vec![build_empty_finding()],
             allowed: vec![build_empty_allowed_finding()],
         }];
@@ -166,6 +172,7 @@ mod tests {
         finding.fingerprint.clone_from(&fingerprint);
         let befores = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![finding],
             allowed: vec![],
         }];
@@ -189,6 +196,7 @@ mod tests {
         finding.fingerprint.clone_from(&fingerprint);
         let befores = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![finding],
             allowed: vec![],
         }];
@@ -209,6 +217,7 @@ mod tests {
         finding_before.fingerprint.clone_from(&fingerprint_before);
         let befores = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![finding_before],
             allowed: vec![],
         }];
@@ -218,6 +227,7 @@ mod tests {
         finding_after.fingerprint.clone_from(&fingerprint_after);
         let afters = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![finding_after],
             allowed: vec![],
         }];
@@ -239,6 +249,7 @@ mod tests {
         let befores = vec![build_empty_filter_result("repo1")];
         let afters = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![finding],
             allowed: vec![],
         }];
@@ -258,6 +269,7 @@ mod tests {
         allowed_finding.finding.fingerprint.clone_from(&fingerprint);
         let befores = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![],
             allowed: vec![allowed_finding],
         }];
@@ -283,6 +295,7 @@ mod tests {
         let befores = vec![];
         let afters = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![finding],
             allowed: vec![],
         }];
@@ -305,6 +318,7 @@ mod tests {
             .clone_from(&fingerprint_before);
         let befores = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![],
             allowed: vec![allowed_finding_before],
         }];
@@ -317,6 +331,7 @@ mod tests {
             .clone_from(&fingerprint_after);
         let afters = vec![FilterResult {
             repo_name: "repo1".to_owned(),
+            metadata: None,
             confirmed: vec![],
             allowed: vec![allowed_finding_after],
         }];
@@ -329,4 +344,22 @@ mod tests {
         assert_eq!(diff[0].allowed[1].finding.fingerprint, fingerprint_after);
         Ok(())
     }
+
+    #[test]
+    fn test_same_remote_url() -> Result {
+        let with_remote = |repo_name: &str| {
+            let mut result = build_empty_filter_result(repo_name);
+            result.metadata = Some(ReportMetadata {
+                repo_name: repo_name.to_owned(),
+                remote_url: Some("https://example.com/org/repo.git".to_owned()),
+                ..Default::default()
+            });
+            result.confirmed.push(build_empty_finding());
+            result
+        };
+        // Renamed report files are matched by the remote URL.
+        let diff = compute_diff(vec![with_remote("old")], vec![with_remote("new")]);
+        assert!(diff.is_empty());
+        Ok(())
+    }
 }
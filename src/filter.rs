@@ -1,13 +1,17 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
     config::{Allowlist, RegexTarget},
-    report::{AllowedFinding, Finding, Report},
+    report::{AllowedFinding, Finding, Report, metadata::ReportMetadata},
 };
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FilterResult {
     pub repo_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ReportMetadata>,
     pub confirmed: Vec<Finding>,
     pub allowed: Vec<AllowedFinding>,
 }
@@ -16,16 +20,38 @@ impl FilterResult {
     pub const fn is_empty(&self) -> bool {
         self.confirmed.is_empty() && self.allowed.is_empty()
     }
+
+    // Remote URLs don't change when report files are renamed, prefer them when both sides have one.
+    pub fn is_same_repo(&self, other: &Self) -> bool {
+        let remote_url = |result: &Self| {
+            result
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.remote_url.clone())
+        };
+        match (remote_url(self), remote_url(other)) {
+            (Some(url), Some(other_url)) => url == other_url,
+            _ => self.repo_name == other.repo_name,
+        }
+    }
 }
 
 pub struct FindingFilter<'vec> {
     allowlist_list: &'vec Vec<Allowlist>,
+    // Whether any allowlist matches against `Line`, which vanilla gitleaks reports don't have.
+    uses_line: bool,
+    warned_missing_line: AtomicBool,
 }
 
 impl<'vec> FindingFilter<'vec> {
-    pub const fn new(allowlist: &'vec Vec<Allowlist>) -> Self {
+    pub fn new(allowlist: &'vec Vec<Allowlist>) -> Self {
+        let uses_line = allowlist
+            .iter()
+            .any(|a| matches!(a.regex_target, Some(RegexTarget::Line)));
         Self {
             allowlist_list: allowlist,
+            uses_line,
+            warned_missing_line: AtomicBool::new(false),
         }
     }
 
@@ -47,12 +73,21 @@ impl<'vec> FindingFilter<'vec> {
                 });
         FilterResult {
             repo_name: report.repo_name,
+            metadata: report.metadata,
             confirmed,
             allowed,
         }
     }
 
-    fn apply(&self, finding: Finding) -> FilteredFinding {
+    pub fn apply(&self, finding: Finding) -> FilteredFinding {
+        if self.uses_line
+            && finding.line.is_none()
+            && !self.warned_missing_line.swap(true, Ordering::Relaxed)
+        {
+            eprintln!(
+                "Warning: findings without `Line` field found, possibly reported by vanilla gitleaks. Allowlists with `regexTarget = \"line\"` are matched against `Match` instead."
+            );
+        }
         let ret = self
             .allowlist_list
             .iter()
@@ -111,7 +146,8 @@ fn apply_allowlist(allowlist: &Allowlist, finding: &Finding) -> bool {
             .regex_target
             .as_ref()
             .map_or(&finding.secret, |regex_target| match regex_target {
-                RegexTarget::Line => &finding.line,
+                // Fall back to `Match` which is the closest to `Line` for vanilla gitleaks reports.
+                RegexTarget::Line => finding.line.as_ref().unwrap_or(&finding.matched),
                 RegexTarget::Match => &finding.matched,
                 RegexTarget::Secret => &finding.secret,
             });
@@ -257,7 +293,19 @@ mod tests {
         set_regex_expr_to_allowlist(&mut allowlist, "^book_key = test-secret")?;
         "test-secret".clone_into(&mut finding.secret);
         "key = test-secret".clone_into(&mut finding.matched);
-        "book_key = test-secret # comment".clone_into(&mut finding.line);
+        finding.line = Some("book_key = test-secret # comment".to_owned());
+        assert_allow(&allowlist, &finding)
+    }
+
+    #[test]
+    fn test_regexes_target_line_fallback_to_match() -> Result {
+        let mut allowlist = build_empty_allowlist();
+        let mut finding = build_empty_finding();
+
+        allowlist.regex_target = Some(RegexTarget::Line);
+        set_regex_expr_to_allowlist(&mut allowlist, "^key = test-secret")?;
+        "key = test-secret".clone_into(&mut finding.matched);
+        finding.line = None;
         assert_allow(&allowlist, &finding)
     }
 }
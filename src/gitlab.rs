@@ -0,0 +1,293 @@
+use anyhow::Context;
+use chrono::Utc;
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+
+use crate::report::Finding;
+
+// GitLab secret detection report.
+// https://gitlab.com/gitlab-org/security-products/security-report-schemas/-/blob/v15.0.7/dist/secret-detection-report-format.json
+#[derive(Debug, Serialize)]
+struct Root {
+    version: &'static str,
+    vulnerabilities: Vec<Vulnerability>,
+    scan: Scan,
+}
+
+#[derive(Debug, Serialize)]
+struct Vulnerability {
+    id: String,
+    category: &'static str,
+    name: String,
+    description: String,
+    severity: &'static str,
+    raw_source_code_extract: String,
+    scanner: Scanner,
+    location: Location,
+    identifiers: Vec<Identifier>,
+}
+
+#[derive(Debug, Serialize)]
+struct Scanner {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    file: String,
+    start_line: usize,
+    end_line: usize,
+    commit: Commit,
+}
+
+#[derive(Debug, Serialize)]
+struct Commit {
+    sha: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    author: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    date: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Identifier {
+    #[serde(rename = "type")]
+    kind: String,
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Scan {
+    analyzer: Tool,
+    scanner: Tool,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    start_time: String,
+    end_time: String,
+    status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    id: &'static str,
+    name: &'static str,
+    version: &'static str,
+    vendor: Vendor,
+}
+
+#[derive(Debug, Serialize)]
+struct Vendor {
+    name: &'static str,
+}
+
+const VERSION: &str = "15.0.7";
+const CATEGORY: &str = "secret_detection";
+// GitLab's own secret detection reports all leaks as critical.
+const SEVERITY: &str = "Critical";
+// GitLab requires a commit, its secret detection uses this for findings outside of git history.
+const UNKNOWN_COMMIT_SHA: &str = "0000000";
+const ANALYZER_NAME: &str = env!("CARGO_PKG_NAME");
+const ANALYZER_VERSION: &str = env!("CARGO_PKG_VERSION");
+const ANALYZER_VENDOR: &str = "Finatext";
+const DEFAULT_SCANNER: &str = "gitleaks";
+// gls doesn't run the scanner here, so the version is unknown.
+const SCANNER_VERSION: &str = "unknown";
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+fn to_vulnerability(finding: Finding) -> Vulnerability {
+    let scanner = finding
+        .scanner
+        .unwrap_or_else(|| DEFAULT_SCANNER.to_owned());
+    let description = if finding.description.is_empty() {
+        format!("`{}` rule finds possible secret", finding.rule_id)
+    } else {
+        finding.description
+    };
+    let commit_sha = if finding.commit.is_empty() {
+        UNKNOWN_COMMIT_SHA.to_owned()
+    } else {
+        finding.commit
+    };
+    Vulnerability {
+        id: vulnerability_id(&finding.fingerprint),
+        category: CATEGORY,
+        name: format!("{} secret", finding.rule_id),
+        description,
+        severity: SEVERITY,
+        raw_source_code_extract: finding.secret,
+        location: Location {
+            file: finding.file,
+            start_line: finding.start_line,
+            end_line: finding.end_line,
+            commit: Commit {
+                sha: commit_sha,
+                author: finding.author,
+                date: finding.date,
+                message: finding.message,
+            },
+        },
+        identifiers: vec![Identifier {
+            kind: format!("{scanner}_rule_id"),
+            name: format!("{scanner} rule ID {}", finding.rule_id),
+            value: finding.rule_id,
+        }],
+        scanner: Scanner {
+            id: scanner.clone(),
+            name: scanner,
+        },
+    }
+}
+
+// Same findings get the same id across runs, GitLab uses ids to track vulnerabilities.
+fn vulnerability_id(fingerprint: &str) -> String {
+    let hash = format!("{:x}", Sha256::digest(fingerprint));
+    let part = |range| hash.get(range).unwrap_or_default();
+    format!(
+        "{}-{}-{}-{}-{}",
+        part(0..8),
+        part(8..12),
+        part(12..16),
+        part(16..20),
+        part(20..32)
+    )
+}
+
+pub fn to_gitlab(findings: Vec<Finding>) -> anyhow::Result<String> {
+    let now = Utc::now().format(TIME_FORMAT).to_string();
+    let root = Root {
+        version: VERSION,
+        vulnerabilities: findings.into_iter().map(to_vulnerability).collect(),
+        scan: Scan {
+            analyzer: Tool {
+                id: ANALYZER_NAME,
+                name: ANALYZER_NAME,
+                version: ANALYZER_VERSION,
+                vendor: Vendor {
+                    name: ANALYZER_VENDOR,
+                },
+            },
+            scanner: Tool {
+                id: DEFAULT_SCANNER,
+                name: DEFAULT_SCANNER,
+                version: SCANNER_VERSION,
+                vendor: Vendor {
+                    name: DEFAULT_SCANNER,
+                },
+            },
+            kind: CATEGORY,
+            start_time: now.clone(),
+            end_time: now,
+            status: "success",
+        },
+    };
+
+    serde_json::to_string_pretty(&root).with_context(|| "Failed to serialize GitLab report")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::report::test::build_empty_finding;
+
+    // Required keys of the secret detection report schema, by JSON pointer of the object.
+    const REQUIRED_KEYS: &[(&str, &[&str])] = &[
+        ("", &["version", "vulnerabilities", "scan"]),
+        (
+            "/scan",
+            &[
+                "analyzer",
+                "scanner",
+                "type",
+                "start_time",
+                "end_time",
+                "status",
+            ],
+        ),
+        ("/scan/analyzer", &["id", "name", "version", "vendor"]),
+        ("/scan/analyzer/vendor", &["name"]),
+        ("/scan/scanner", &["id", "name", "version", "vendor"]),
+        ("/scan/scanner/vendor", &["name"]),
+        ("/vulnerabilities/0", &["id", "identifiers", "location"]),
+        (
+            "/vulnerabilities/0/identifiers/0",
+            &["type", "name", "value"],
+        ),
+        ("/vulnerabilities/0/location", &["commit"]),
+        ("/vulnerabilities/0/location/commit", &["sha"]),
+    ];
+
+    #[test]
+    fn test_vulnerability_id() {
+        let id = vulnerability_id("abc:secret.txt:test:2");
+        assert_eq!(id, vulnerability_id("abc:secret.txt:test:2"));
+        assert_ne!(id, vulnerability_id("abc:secret.txt:test:3"));
+        let lengths = id.split('-').map(str::len).collect::<Vec<_>>();
+        assert_eq!(lengths, [8, 4, 4, 4, 12]);
+        assert!(id.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_to_vulnerability() {
+        let finding = build_empty_finding();
+        let fingerprint = finding.fingerprint.clone();
+        let vulnerability = to_vulnerability(finding);
+        // Ids depend only on fingerprints, so the same finding keeps its id when other fields change.
+        assert_eq!(vulnerability.id, vulnerability_id(&fingerprint));
+        assert_eq!(vulnerability.location.file, "test-file");
+        assert_eq!(vulnerability.location.start_line, 1);
+        assert_eq!(
+            vulnerability.identifiers.first().unwrap().kind,
+            "gitleaks_rule_id"
+        );
+
+        let mut finding = build_empty_finding();
+        finding.commit = String::new();
+        finding.description = String::new();
+        finding.scanner = Some("trufflehog".to_owned());
+        let vulnerability = to_vulnerability(finding);
+        assert_eq!(vulnerability.location.commit.sha, UNKNOWN_COMMIT_SHA);
+        assert_eq!(
+            vulnerability.description,
+            "`test-rule` rule finds possible secret"
+        );
+        assert_eq!(vulnerability.scanner.id, "trufflehog");
+    }
+
+    #[test]
+    fn test_to_gitlab_schema() -> anyhow::Result<()> {
+        let report = serde_json::from_str::<Value>(&to_gitlab(vec![build_empty_finding()])?)?;
+        for (pointer, keys) in REQUIRED_KEYS {
+            let object = report
+                .pointer(pointer)
+                .and_then(Value::as_object)
+                .with_context(|| format!("`{pointer}` is not an object"))?;
+            for key in *keys {
+                assert!(object.contains_key(*key), "`{pointer}` has no `{key}`");
+            }
+        }
+
+        let scan = |key: &str| report.pointer(&format!("/scan/{key}")).cloned();
+        assert_eq!(scan("type"), Some(Value::from("secret_detection")));
+        assert_eq!(scan("status"), Some(Value::from("success")));
+        for key in ["start_time", "end_time"] {
+            let time = scan(key).and_then(|time| time.as_str().map(ToOwned::to_owned));
+            NaiveDateTime::parse_from_str(&time.unwrap_or_default(), TIME_FORMAT)?;
+        }
+        assert_eq!(
+            report.pointer("/vulnerabilities/0/location/file"),
+            Some(&Value::from("test-file"))
+        );
+        assert_eq!(
+            report.pointer("/vulnerabilities/0/location/start_line"),
+            Some(&Value::from(1))
+        );
+        Ok(())
+    }
+}
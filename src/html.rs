@@ -0,0 +1,341 @@
+use std::{collections::BTreeMap, io::Write};
+
+use crate::report::{AllowedFinding, Finding};
+
+const STYLE: &str = include_str!("data/review.css");
+const SCRIPT: &str = include_str!("data/review.js");
+
+// Single static HTML file of review results: summary charts and a sortable, filterable table of
+// findings. Styles and scripts are embedded, so it can be opened without network access.
+#[derive(Debug, Default)]
+pub struct HtmlReport {
+    rows: Vec<Row>,
+    by_repo: BTreeMap<String, Counts>,
+    by_rule_id: BTreeMap<String, Counts>,
+    by_allowlist: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    confirmed: usize,
+    allowed: usize,
+}
+
+#[derive(Debug)]
+struct Row {
+    repo_name: String,
+    allowlist: Option<String>,
+    rule_id: String,
+    file: String,
+    start_line: usize,
+    secret: String,
+    matched: String,
+    line: Option<String>,
+    commit: String,
+}
+
+impl HtmlReport {
+    // Repositories without findings are counted too.
+    pub fn add_repo(&mut self, repo_name: &str) {
+        self.by_repo.entry(repo_name.to_owned()).or_default();
+    }
+
+    pub fn add_confirmed(&mut self, repo_name: &str, finding: &Finding) {
+        self.by_repo
+            .entry(repo_name.to_owned())
+            .or_default()
+            .confirmed += 1;
+        self.by_rule_id
+            .entry(finding.rule_id.clone())
+            .or_default()
+            .confirmed += 1;
+        self.rows.push(Row::new(repo_name, None, finding));
+    }
+
+    pub fn add_allowed(&mut self, repo_name: &str, allowed_finding: &AllowedFinding) {
+        let finding = &allowed_finding.finding;
+        self.by_repo
+            .entry(repo_name.to_owned())
+            .or_default()
+            .allowed += 1;
+        self.by_rule_id
+            .entry(finding.rule_id.clone())
+            .or_default()
+            .allowed += 1;
+        *self
+            .by_allowlist
+            .entry(allowed_finding.allow_rule_id.clone())
+            .or_default() += 1;
+        self.rows.push(Row::new(
+            repo_name,
+            Some(&allowed_finding.allow_rule_id),
+            finding,
+        ));
+    }
+
+    pub fn write(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        let confirmed = self.by_rule_id.values().map(|c| c.confirmed).sum::<usize>();
+        let allowed = self.by_rule_id.values().map(|c| c.allowed).sum::<usize>();
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, r#"<html lang="en">"#)?;
+        writeln!(
+            out,
+            r#"<head><meta charset="utf-8"><title>gls review</title>"#
+        )?;
+        writeln!(out, "<style>\n{STYLE}</style></head>")?;
+        writeln!(out, "<body>\n<h1>gls review</h1>")?;
+
+        writeln!(out, r#"<section class="overview">"#)?;
+        for (label, count) in [
+            ("repositories", self.by_repo.len()),
+            ("total findings", confirmed + allowed),
+            ("allowed findings", allowed),
+            ("confirmed findings", confirmed),
+        ] {
+            writeln!(
+                out,
+                r#"<div class="stat"><span class="count">{count}</span><span>{label}</span></div>"#
+            )?;
+        }
+        writeln!(out, "</section>")?;
+
+        writeln!(out, r#"<section class="charts">"#)?;
+        write_chart(
+            out,
+            "Findings per rule",
+            self.by_rule_id
+                .iter()
+                .map(|(rule_id, counts)| (rule_id.as_str(), counts.confirmed, counts.allowed)),
+        )?;
+        write_chart(
+            out,
+            "Allowed findings per allowlist",
+            self.by_allowlist
+                .iter()
+                .map(|(allowlist, count)| (allowlist.as_str(), 0, *count)),
+        )?;
+        write_chart(
+            out,
+            "Findings per repository",
+            self.by_repo
+                .iter()
+                .map(|(repo_name, counts)| (repo_name.as_str(), counts.confirmed, counts.allowed)),
+        )?;
+        writeln!(out, "</section>")?;
+
+        self.write_filters(out)?;
+        self.write_table(out)?;
+        writeln!(out, "<script>\n{SCRIPT}</script>\n</body>\n</html>")?;
+        Ok(())
+    }
+
+    fn write_filters(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        writeln!(out, r#"<section class="filters">"#)?;
+        writeln!(
+            out,
+            r#"<input id="filter" type="search" placeholder="Filter by any text">"#
+        )?;
+        let statuses = ["confirmed", "allowed"];
+        let filters = [
+            ("status", "all statuses", statuses.to_vec()),
+            (
+                "repo",
+                "all repositories",
+                self.by_repo.keys().map(String::as_str).collect(),
+            ),
+            (
+                "rule",
+                "all rules",
+                self.by_rule_id.keys().map(String::as_str).collect(),
+            ),
+            (
+                "allowlist",
+                "all allowlists",
+                self.by_allowlist.keys().map(String::as_str).collect(),
+            ),
+        ];
+        for (column, label, values) in filters {
+            write!(
+                out,
+                r#"<select data-column="{column}"><option value="">{label}</option>"#
+            )?;
+            for value in values {
+                let value = escape(value);
+                write!(out, r#"<option value="{value}">{value}</option>"#)?;
+            }
+            writeln!(out, "</select>")?;
+        }
+        writeln!(
+            out,
+            r#"<span><span id="shown">{}</span> / {} findings</span>"#,
+            self.rows.len(),
+            self.rows.len()
+        )?;
+        writeln!(out, "</section>")?;
+        Ok(())
+    }
+
+    fn write_table(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        writeln!(out, r#"<table id="findings"><thead><tr>"#)?;
+        for (header, kind) in [
+            ("status", "text"),
+            ("repo", "text"),
+            ("rule_id", "text"),
+            ("allowlist", "text"),
+            ("file", "text"),
+            ("line", "number"),
+            ("secret", "text"),
+            ("context", "text"),
+        ] {
+            write!(out, r#"<th data-type="{kind}">{header}</th>"#)?;
+        }
+        writeln!(out, "</tr></thead>\n<tbody>")?;
+        for row in &self.rows {
+            row.write(out)?;
+        }
+        writeln!(out, "</tbody></table>")?;
+        Ok(())
+    }
+}
+
+impl Row {
+    fn new(repo_name: &str, allowlist: Option<&str>, finding: &Finding) -> Self {
+        Self {
+            repo_name: repo_name.to_owned(),
+            allowlist: allowlist.map(ToOwned::to_owned),
+            rule_id: finding.rule_id.clone(),
+            file: finding.file.clone(),
+            start_line: finding.start_line,
+            secret: finding.secret.clone(),
+            matched: finding.matched.clone(),
+            line: finding.line.clone(),
+            commit: finding.commit.clone(),
+        }
+    }
+
+    fn write(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        let status = if self.allowlist.is_some() {
+            "allowed"
+        } else {
+            "confirmed"
+        };
+        let repo_name = escape(&self.repo_name);
+        let rule_id = escape(&self.rule_id);
+        let allowlist = escape(self.allowlist.as_deref().unwrap_or_default());
+        write!(
+            out,
+            r#"<tr class="{status}" data-status="{status}" data-repo="{repo_name}" data-rule="{rule_id}" data-allowlist="{allowlist}">"#
+        )?;
+        write!(
+            out,
+            "<td>{status}</td><td>{repo_name}</td><td>{rule_id}</td><td>{allowlist}</td>"
+        )?;
+        write!(
+            out,
+            r#"<td class="file">{}</td><td>{}</td><td><code>{}</code></td>"#,
+            escape(&self.file),
+            self.start_line,
+            escape(&self.secret)
+        )?;
+        write!(
+            out,
+            "<td><details><summary>show</summary><dl><dt>match</dt><dd><pre>{}</pre></dd>",
+            escape(&self.matched)
+        )?;
+        if let Some(line) = &self.line {
+            write!(out, "<dt>line</dt><dd><pre>{}</pre></dd>", escape(line))?;
+        }
+        if !self.commit.is_empty() {
+            write!(
+                out,
+                "<dt>commit</dt><dd><code>{}</code></dd>",
+                escape(&self.commit)
+            )?;
+        }
+        writeln!(out, "</dl></details></td></tr>")?;
+        Ok(())
+    }
+}
+
+// Horizontal bars of confirmed and allowed counts, drawn with CSS only. Widths are rounded up so
+// that small counts are still visible.
+fn write_chart<'item>(
+    out: &mut dyn Write,
+    title: &str,
+    items: impl Iterator<Item = (&'item str, usize, usize)> + Clone,
+) -> anyhow::Result<()> {
+    let max = items
+        .clone()
+        .map(|(_, confirmed, allowed)| confirmed + allowed)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    writeln!(
+        out,
+        r#"<figure class="chart"><figcaption>{title}</figcaption>"#
+    )?;
+    for (label, confirmed, allowed) in items {
+        let label = escape(label);
+        write!(
+            out,
+            r#"<div class="bar"><span class="label" title="{label}">{label}</span><span class="track">"#
+        )?;
+        for (class, count) in [("confirmed", confirmed), ("allowed", allowed)] {
+            if count > 0 {
+                write!(
+                    out,
+                    r#"<span class="fill {class}" style="width: {}%" title="{count} {class}"></span>"#,
+                    (count * 100).div_ceil(max)
+                )?;
+            }
+        }
+        writeln!(
+            out,
+            r#"</span><span class="count">{}</span></div>"#,
+            confirmed + allowed
+        )?;
+    }
+    writeln!(out, "</figure>")?;
+    Ok(())
+}
+
+fn escape(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            match c {
+                '&' => acc.push_str("&amp;"),
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '"' => acc.push_str("&quot;"),
+                '\'' => acc.push_str("&#39;"),
+                _ => acc.push(c),
+            }
+            acc
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test::{build_empty_allowed_finding, build_empty_finding};
+
+    #[test]
+    fn test_write() -> anyhow::Result<()> {
+        let mut report = HtmlReport::default();
+        report.add_repo("empty-repo");
+        let mut finding = build_empty_finding();
+        finding.secret = "<script>\"x\"".to_owned();
+        report.add_confirmed("repo", &finding);
+        report.add_allowed("repo", &build_empty_allowed_finding());
+
+        let mut out = Vec::new();
+        report.write(&mut out)?;
+        let html = String::from_utf8(out)?;
+        assert!(html.contains(r#"<span class="count">2</span><span>repositories</span>"#));
+        assert!(html.contains("<code>&lt;script&gt;&quot;x&quot;</code>"));
+        assert!(!html.contains("<script>\"x\""));
+        assert!(html.contains(r#"data-status="allowed" data-repo="repo" data-rule="test-rule" data-allowlist="test-allow-rule""#));
+        assert!(html.contains(r#"<option value="test-allow-rule">test-allow-rule</option>"#));
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use std::{collections::BTreeMap, io::Write};
+
+use crate::report::{AllowedFinding, Finding};
+
+// JUnit XML where each rule is a test suite, confirmed findings are failed test cases and allowed
+// findings are skipped ones. Test cases are grouped by rules, so they are kept until written.
+#[derive(Debug, Default)]
+pub struct JunitReport {
+    suites: BTreeMap<String, Vec<TestCase>>,
+}
+
+#[derive(Debug)]
+struct TestCase {
+    name: String,
+    classname: String,
+    outcome: Outcome,
+}
+
+#[derive(Debug)]
+enum Outcome {
+    Failure { message: String, details: String },
+    Skipped { message: String },
+}
+
+impl JunitReport {
+    pub fn add_confirmed(&mut self, repo_name: &str, finding: &Finding) {
+        let details = format!(
+            "{}:{}-{} in commit {}\nsecret: {}",
+            finding.file, finding.start_line, finding.end_line, finding.commit, finding.secret
+        );
+        self.add(
+            repo_name,
+            finding,
+            Outcome::Failure {
+                message: format!("`{}` rule finds possible secret", finding.rule_id),
+                details,
+            },
+        );
+    }
+
+    pub fn add_allowed(&mut self, repo_name: &str, allowed_finding: &AllowedFinding) {
+        self.add(
+            repo_name,
+            &allowed_finding.finding,
+            Outcome::Skipped {
+                message: format!("Allowed by `{}` allowlist", allowed_finding.allow_rule_id),
+            },
+        );
+    }
+
+    fn add(&mut self, repo_name: &str, finding: &Finding, outcome: Outcome) {
+        self.suites
+            .entry(finding.rule_id.clone())
+            .or_default()
+            .push(TestCase {
+                name: format!("{}:{}", finding.file, finding.start_line),
+                classname: repo_name.to_owned(),
+                outcome,
+            });
+    }
+
+    pub fn write(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        let cases = || self.suites.values().flatten();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<testsuites name="{}" tests="{}" failures="{}" skipped="{}">"#,
+            env!("CARGO_PKG_NAME"),
+            cases().count(),
+            count_failures(cases()),
+            count_skipped(cases()),
+        )?;
+        for (rule_id, cases) in &self.suites {
+            writeln!(
+                out,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}">"#,
+                escape(rule_id),
+                cases.len(),
+                count_failures(cases),
+                count_skipped(cases),
+            )?;
+            for case in cases {
+                write!(
+                    out,
+                    r#"    <testcase name="{}" classname="{}">"#,
+                    escape(&case.name),
+                    escape(&case.classname)
+                )?;
+                match &case.outcome {
+                    Outcome::Failure { message, details } => write!(
+                        out,
+                        r#"<failure message="{}" type="secret">{}</failure>"#,
+                        escape(message),
+                        escape(details)
+                    )?,
+                    Outcome::Skipped { message } => {
+                        write!(out, r#"<skipped message="{}"/>"#, escape(message))?;
+                    }
+                }
+                writeln!(out, "</testcase>")?;
+            }
+            writeln!(out, "  </testsuite>")?;
+        }
+        writeln!(out, "</testsuites>")?;
+        Ok(())
+    }
+}
+
+fn count_failures<'case>(cases: impl IntoIterator<Item = &'case TestCase>) -> usize {
+    cases
+        .into_iter()
+        .filter(|case| matches!(case.outcome, Outcome::Failure { .. }))
+        .count()
+}
+
+fn count_skipped<'case>(cases: impl IntoIterator<Item = &'case TestCase>) -> usize {
+    cases
+        .into_iter()
+        .filter(|case| matches!(case.outcome, Outcome::Skipped { .. }))
+        .count()
+}
+
+// Secrets can contain anything, drop control characters which are not allowed in XML 1.0.
+fn escape(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            match c {
+                '&' => acc.push_str("&amp;"),
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '"' => acc.push_str("&quot;"),
+                '\'' => acc.push_str("&apos;"),
+                _ => acc.push(c),
+            }
+            acc
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test::{build_empty_allowed_finding, build_empty_finding};
+
+    #[test]
+    fn test_write() -> anyhow::Result<()> {
+        let mut report = JunitReport::default();
+        let mut finding = build_empty_finding();
+        finding.secret = "<a&b>\u{1}".to_owned();
+        report.add_confirmed("repo", &finding);
+        report.add_allowed("repo", &build_empty_allowed_finding());
+
+        let mut out = Vec::new();
+        report.write(&mut out)?;
+        let xml = String::from_utf8(out)?;
+        assert!(xml.contains(r#"<testsuites name="gls" tests="2" failures="1" skipped="1">"#));
+        assert!(xml.contains(r#"<testsuite name="test-rule" tests="2" failures="1" skipped="1">"#));
+        assert!(xml.contains(r#"<testcase name="test-file:1" classname="repo">"#));
+        assert!(xml.contains("secret: &lt;a&amp;b&gt;</failure>"));
+        assert!(xml.contains(r#"<skipped message="Allowed by `test-allow-rule` allowlist"/>"#));
+        Ok(())
+    }
+}
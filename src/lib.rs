@@ -2,12 +2,20 @@
 //#![feature(lint_reasons)]
 //#![warn(clippy::allow_attributes, clippy::allow_attributes_without_reason)]
 
+mod baseline;
 mod config;
+mod csv_output;
 mod diff;
 mod filter;
+mod gitlab;
 mod gitleaks_config;
+mod html;
+mod junit;
+mod rdjson;
+mod redact;
 mod report;
 mod sarif;
+mod severity;
 
 pub mod cli;
 
@@ -18,9 +26,9 @@ use std::{
 
 use anyhow::{Context as _, Result};
 
-fn collect_dir<B, F>(path: &Path, f: F) -> Result<Vec<B>>
+fn collect_dir<B, F>(path: &Path, mut f: F) -> Result<Vec<B>>
 where
-    F: Fn(Vec<B>, PathBuf) -> Result<Vec<B>>,
+    F: FnMut(Vec<B>, PathBuf) -> Result<Vec<B>>,
 {
     read_dir(path)
         .with_context(|| format!("Failed to read path: {}", path.display()))?
@@ -0,0 +1,260 @@
+use std::io::Write;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::{
+    report::Finding,
+    severity::{Severities, Severity},
+};
+
+// reviewdog Diagnostic Format.
+// https://github.com/reviewdog/reviewdog/tree/v0.20.2/proto/rdf
+#[derive(Debug, Serialize)]
+struct DiagnosticResult {
+    source: Source,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+struct Diagnostic {
+    message: String,
+    location: Location,
+    severity: &'static str,
+    source: Source,
+    code: Code,
+}
+
+#[derive(Debug, Serialize)]
+struct Source {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    path: String,
+    range: Range,
+}
+
+#[derive(Debug, Serialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Debug, Serialize)]
+struct Position {
+    line: usize,
+    // Reports of other scanners don't have columns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct Code {
+    value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+const SOURCE_NAME: &str = env!("CARGO_PKG_NAME");
+const SOURCE_URL: &str = env!("CARGO_PKG_HOMEPAGE");
+const DEFAULT_SCANNER: &str = "gitleaks";
+
+const fn to_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "INFO",
+        Severity::Warning => "WARNING",
+        Severity::Error => "ERROR",
+    }
+}
+
+fn position(line: usize, column: usize) -> Position {
+    Position {
+        line,
+        column: (column > 0).then_some(column),
+    }
+}
+
+fn to_diagnostic(finding: Finding, severities: &Severities, guide: Option<&str>) -> Diagnostic {
+    let severity = to_level(severities.severity(&finding));
+    Diagnostic {
+        message: format!("`{}` is considered as secret value.", finding.secret),
+        location: Location {
+            path: finding.file,
+            range: Range {
+                start: position(finding.start_line, finding.start_column),
+                end: position(finding.end_line, finding.end_column),
+            },
+        },
+        severity,
+        source: Source {
+            name: finding
+                .scanner
+                .unwrap_or_else(|| DEFAULT_SCANNER.to_owned()),
+            url: None,
+        },
+        code: Code {
+            value: finding.rule_id,
+            url: guide.map(ToOwned::to_owned),
+        },
+    }
+}
+
+pub fn to_rdjson(
+    findings: Vec<Finding>,
+    severities: &Severities,
+    guide: Option<&str>,
+) -> anyhow::Result<String> {
+    let result = DiagnosticResult {
+        source: Source {
+            name: SOURCE_NAME.to_owned(),
+            url: Some(SOURCE_URL),
+        },
+        diagnostics: findings
+            .into_iter()
+            .map(|finding| to_diagnostic(finding, severities, guide))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&result).with_context(|| "Failed to serialize rdjson report")
+}
+
+// One diagnostic per line, for `reviewdog -f=rdjsonl`.
+pub fn write_rdjsonl(
+    findings: Vec<Finding>,
+    severities: &Severities,
+    guide: Option<&str>,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    for finding in findings {
+        let diagnostic = to_diagnostic(finding, severities, guide);
+        serde_json::to_writer(&mut *out, &diagnostic)
+            .with_context(|| "Failed to serialize rdjsonl diagnostic")?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{config::SeverityConfig, report::test::build_empty_finding};
+
+    fn build_severities() -> anyhow::Result<Severities> {
+        Ok(Severities::new(
+            SeverityConfig::default(),
+            vec!["test-rule=error".parse()?],
+        ))
+    }
+
+    #[test]
+    fn test_to_level() {
+        assert_eq!(to_level(Severity::Info), "INFO");
+        assert_eq!(to_level(Severity::Warning), "WARNING");
+        assert_eq!(to_level(Severity::Error), "ERROR");
+    }
+
+    #[test]
+    fn test_range() -> anyhow::Result<()> {
+        let severities = build_severities()?;
+        let mut finding = build_empty_finding();
+        finding.start_line = 2;
+        finding.end_line = 4;
+        finding.start_column = 3;
+        finding.end_column = 7;
+        let diagnostic = serde_json::to_value(to_diagnostic(finding, &severities, None))?;
+        // Multi-line findings span from the start position to the end position of the report.
+        assert_eq!(
+            diagnostic.pointer("/location/range"),
+            Some(&json!({
+                "start": {"line": 2, "column": 3},
+                "end": {"line": 4, "column": 7},
+            }))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_without_line() -> anyhow::Result<()> {
+        let severities = build_severities()?;
+        let mut finding = build_empty_finding();
+        finding.line = None;
+        finding.start_column = 0;
+        finding.end_column = 0;
+        finding.scanner = Some("trufflehog".to_owned());
+        let diagnostic = serde_json::to_value(to_diagnostic(finding, &severities, None))?;
+        assert_eq!(
+            diagnostic.pointer("/location/range"),
+            Some(&json!({"start": {"line": 1}, "end": {"line": 1}}))
+        );
+        assert_eq!(
+            diagnostic.pointer("/source/name"),
+            Some(&Value::from("trufflehog"))
+        );
+        assert_eq!(
+            diagnostic.pointer("/message"),
+            Some(&Value::from("`test-secret` is considered as secret value."))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_rdjson() -> anyhow::Result<()> {
+        let severities = build_severities()?;
+        let mut other = build_empty_finding();
+        other.rule_id = "other".to_owned();
+        let rdjson = to_rdjson(
+            vec![build_empty_finding(), other],
+            &severities,
+            Some("https://example.com/guide"),
+        )?;
+        let result = serde_json::from_str::<Value>(&rdjson)?;
+        assert_eq!(result.pointer("/source/name"), Some(&Value::from("gls")));
+        assert_eq!(
+            result.pointer("/diagnostics/0/severity"),
+            Some(&Value::from("ERROR"))
+        );
+        assert_eq!(
+            result.pointer("/diagnostics/1/severity"),
+            Some(&Value::from("WARNING"))
+        );
+        assert_eq!(
+            result.pointer("/diagnostics/0/code"),
+            Some(&json!({"value": "test-rule", "url": "https://example.com/guide"}))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_rdjsonl() -> anyhow::Result<()> {
+        let severities = build_severities()?;
+        let mut out = Vec::new();
+        write_rdjsonl(
+            vec![build_empty_finding(), build_empty_finding()],
+            &severities,
+            None,
+            &mut out,
+        )?;
+        let rdjsonl = String::from_utf8(out)?;
+        assert!(rdjsonl.ends_with('\n'));
+        let lines = rdjsonl.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let diagnostic = serde_json::from_str::<Value>(line)?;
+            assert_eq!(
+                diagnostic.pointer("/code/value"),
+                Some(&Value::from("test-rule"))
+            );
+            assert_eq!(diagnostic.pointer("/code/url"), None);
+        }
+
+        let mut out = Vec::new();
+        write_rdjsonl(vec![], &severities, None, &mut out)?;
+        assert!(out.is_empty());
+        Ok(())
+    }
+}
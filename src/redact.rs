@@ -0,0 +1,287 @@
+use std::str::FromStr;
+
+use anyhow::{Context as _, bail};
+use serde_json::Value;
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    filter::{FilterResult, FilteredFinding},
+    report::{AllowedFinding, Finding},
+};
+
+const REDACTED: &str = "REDACTED";
+const MASK: &str = "***";
+const HASH_LENGTH: usize = 16;
+
+// How secrets are shown in outputs. Findings are filtered with the original secrets, only outputs
+// are redacted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Redaction {
+    #[default]
+    None,
+    Full,
+    // Keep the first and the last N characters.
+    Keep(usize),
+    // Prefix of SHA-256 hash, to tell the same secrets without showing them. Plain hashes of
+    // guessable secrets can be brute-forced, HMAC-SHA256 with the key is used when it's given.
+    Hash(Option<Vec<u8>>),
+}
+
+impl FromStr for Redaction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "full" => Ok(Self::Full),
+            "hash" => Ok(Self::Hash(None)),
+            _ => {
+                let Some(n) = s.strip_prefix("keep:") else {
+                    bail!("Unknown redaction `{s}`, expected none, full, keep:<N> or hash");
+                };
+                let n = n
+                    .parse()
+                    .with_context(|| format!("Invalid number of characters to keep: {s}"))?;
+                Ok(Self::Keep(n))
+            }
+        }
+    }
+}
+
+// Whether the secret looks like an output of `Redaction::redact`, original secrets rarely do.
+pub fn is_redacted(secret: &str) -> bool {
+    secret == REDACTED
+        || secret.contains(MASK)
+        || secret
+            .strip_prefix("sha256:")
+            .or_else(|| secret.strip_prefix("hmac-sha256:"))
+            .is_some_and(|hash| {
+                hash.len() == HASH_LENGTH && hash.chars().all(|c| c.is_ascii_hexdigit())
+            })
+}
+
+// HMAC-SHA256 of RFC 2104, keys longer than the block are hashed first.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> String {
+    const BLOCK_SIZE: usize = 64;
+    let key = if key.len() > BLOCK_SIZE {
+        Sha256::digest(key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    let pad = |byte: u8| {
+        (0..BLOCK_SIZE)
+            .map(|i| key.get(i).copied().unwrap_or_default() ^ byte)
+            .collect::<Vec<_>>()
+    };
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    let outer = Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize();
+    format!("{outer:x}")
+}
+
+// Commit messages and unknown fields rarely contain secrets, but nothing prevents them.
+fn redact_value(value: &mut Value, secret: &str, redacted: &str) {
+    match value {
+        Value::String(s) => {
+            if s.contains(secret) {
+                *s = s.replace(secret, redacted);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact_value(value, secret, redacted);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                redact_value(value, secret, redacted);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+impl Redaction {
+    // The key only affects `hash`, other redactions don't show anything derived from secrets.
+    #[must_use]
+    pub fn with_key(self, key: Option<&str>) -> Self {
+        match self {
+            Self::Hash(_) => Self::Hash(key.map(|key| key.as_bytes().to_vec())),
+            Self::None | Self::Full | Self::Keep(_) => self,
+        }
+    }
+
+    pub fn redact(&self, secret: &str) -> String {
+        match self {
+            Self::None => secret.to_owned(),
+            Self::Full => REDACTED.to_owned(),
+            &Self::Keep(n) => {
+                let len = secret.chars().count();
+                // Keeping both ends of short secrets shows them entirely.
+                if len <= n * 2 {
+                    return REDACTED.to_owned();
+                }
+                let first = secret.chars().take(n).collect::<String>();
+                let last = secret.chars().skip(len - n).collect::<String>();
+                format!("{first}{MASK}{last}")
+            }
+            Self::Hash(None) => {
+                let hash = format!("{:x}", Sha256::digest(secret));
+                format!("sha256:{}", hash.get(..HASH_LENGTH).unwrap_or_default())
+            }
+            Self::Hash(Some(key)) => {
+                let hash = hmac_sha256(key, secret.as_bytes());
+                format!(
+                    "hmac-sha256:{}",
+                    hash.get(..HASH_LENGTH).unwrap_or_default()
+                )
+            }
+        }
+    }
+
+    // `Match` and `Line` contain the secret too, and so may `Message` and unknown fields.
+    pub fn redact_finding(&self, mut finding: Finding) -> Finding {
+        if *self == Self::None || finding.secret.is_empty() {
+            return finding;
+        }
+        let redacted = self.redact(&finding.secret);
+        finding.matched = finding.matched.replace(&finding.secret, &redacted);
+        finding.line = finding
+            .line
+            .map(|line| line.replace(&finding.secret, &redacted));
+        finding.message = finding.message.replace(&finding.secret, &redacted);
+        for value in finding.extra.values_mut() {
+            redact_value(value, &finding.secret, &redacted);
+        }
+        finding.secret = redacted;
+        finding
+    }
+
+    pub fn redact_allowed(&self, mut allowed_finding: AllowedFinding) -> AllowedFinding {
+        allowed_finding.finding = self.redact_finding(allowed_finding.finding);
+        allowed_finding
+    }
+
+    pub fn redact_filtered(&self, filtered: FilteredFinding) -> FilteredFinding {
+        match filtered {
+            FilteredFinding::Confirmed(finding) => {
+                FilteredFinding::Confirmed(self.redact_finding(finding))
+            }
+            FilteredFinding::Allowed(allowed_finding) => {
+                FilteredFinding::Allowed(self.redact_allowed(allowed_finding))
+            }
+        }
+    }
+
+    pub fn redact_result(&self, mut result: FilterResult) -> FilterResult {
+        result.confirmed = self.redact_findings(result.confirmed);
+        result.allowed = self.redact_allowed_findings(result.allowed);
+        result
+    }
+
+    pub fn redact_findings(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        findings
+            .into_iter()
+            .map(|finding| self.redact_finding(finding))
+            .collect()
+    }
+
+    pub fn redact_allowed_findings(&self, allowed: Vec<AllowedFinding>) -> Vec<AllowedFinding> {
+        allowed
+            .into_iter()
+            .map(|allowed_finding| self.redact_allowed(allowed_finding))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test::build_empty_finding;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("none".parse::<Redaction>()?, Redaction::None);
+        assert_eq!("full".parse::<Redaction>()?, Redaction::Full);
+        assert_eq!("keep:4".parse::<Redaction>()?, Redaction::Keep(4));
+        assert_eq!("hash".parse::<Redaction>()?, Redaction::Hash(None));
+        "keep:x".parse::<Redaction>().unwrap_err();
+        "partial".parse::<Redaction>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(Redaction::None.redact("deadbeef"), "deadbeef");
+        assert_eq!(Redaction::Full.redact("deadbeef"), "REDACTED");
+        assert_eq!(Redaction::Keep(2).redact("deadbeef"), "de***ef");
+        assert_eq!(Redaction::Keep(4).redact("deadbeef"), "REDACTED");
+        assert_eq!(
+            Redaction::Hash(None).redact("deadbeef"),
+            "sha256:2baf1f40105d9501"
+        );
+        let keyed = Redaction::Hash(None).with_key(Some("test-key"));
+        assert_eq!(keyed, Redaction::Hash(Some(b"test-key".to_vec())));
+        assert_eq!(keyed.redact("deadbeef"), "hmac-sha256:bc1f1da980450a70");
+        assert_eq!(Redaction::Full.with_key(Some("test-key")), Redaction::Full);
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test cases 2 and 6.
+        assert_eq!(
+            hmac_sha256(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_is_redacted() {
+        for redaction in [
+            Redaction::Full,
+            Redaction::Keep(2),
+            Redaction::Hash(None),
+            Redaction::Hash(Some(b"test-key".to_vec())),
+        ] {
+            assert!(is_redacted(&redaction.redact("deadbeef")), "{redaction:?}");
+        }
+        assert!(!is_redacted("deadbeef"));
+        assert!(!is_redacted("sha256:deadbeef"));
+    }
+
+    #[test]
+    fn test_redact_finding() {
+        let finding = Redaction::Full.redact_finding(build_empty_finding());
+        assert_eq!(finding.secret, "REDACTED");
+        assert_eq!(finding.matched, "key = 'REDACTED'");
+        assert_eq!(
+            finding.line.as_deref(),
+            Some("test-line: key = 'REDACTED' # comment")
+        );
+
+        let mut finding = build_empty_finding();
+        finding.message = "add test-secret".to_owned();
+        finding.extra.insert(
+            "Link".to_owned(),
+            serde_json::json!({"url": "https://example.com/test-secret", "lines": [1]}),
+        );
+        let finding = Redaction::Full.redact_finding(finding);
+        assert_eq!(finding.message, "add REDACTED");
+        assert_eq!(
+            finding.extra.get("Link"),
+            Some(&serde_json::json!({"url": "https://example.com/REDACTED", "lines": [1]}))
+        );
+    }
+}
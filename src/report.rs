@@ -1,30 +1,310 @@
+mod csv_report;
+mod detect_secrets_report;
+pub mod metadata;
+mod sarif_report;
+mod trufflehog_report;
+
 use std::{
-    fs::read_to_string,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    fs::File,
     hash::{Hash, Hasher},
-    path::Path,
+    io::{self, BufReader, Read, stdin},
+    path::{Path, PathBuf},
+    sync::Once,
 };
 
 use anyhow::Context as _;
-use serde::{Deserialize, Serialize};
+use clap::ValueEnum;
+use flate2::{Compression as GzipLevel, read::MultiGzDecoder, write::GzEncoder};
+use serde::{
+    Deserialize, Deserializer as _, Serialize,
+    de::{SeqAccess, Visitor},
+};
+use serde_json::Value;
+use zstd::{Decoder as ZstdDecoder, Encoder as ZstdEncoder};
 
-pub fn read_report(path: &Path) -> anyhow::Result<Report> {
-    let repo_name = path
+use crate::report::metadata::{ReportMetadata, read_metadata};
+
+// Report formats of gitleaks. JSON is the canonical one, others are converted to `Finding` with
+// missing fields defaulted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Sarif,
+    Csv,
+    // Reports of other scanners, never inferred from file extensions since they are JSON too.
+    Trufflehog,
+    DetectSecrets,
+}
+
+impl ReportFormat {
+    // Compressed reports are detected by the extension before the compression one, e.g. `repo.json.gz`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match strip_compression(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            Some("json") => Some(Self::Json),
+            Some("sarif") => Some(Self::Sarif),
+            Some("csv") => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    // Use explicitly specified format if present, otherwise infer from the file extension.
+    pub fn resolve(format: Option<Self>, path: &Path) -> anyhow::Result<Self> {
+        // Reports from stdin have no extension, default to gitleaks' default format.
+        format
+            .or_else(|| Self::from_path(path))
+            .or_else(|| is_stdin(path).then_some(Self::Json))
+            .with_context(|| {
+                format!(
+                    "Unknown report file extension, expected .json, .sarif or .csv, or specify the input format: {}",
+                    path.display()
+                )
+            })
+    }
+
+    const fn defaulted_fields(self) -> &'static [&'static str] {
+        match self {
+            Self::Json => &[],
+            Self::Sarif => sarif_report::DEFAULTED_FIELDS,
+            Self::Csv => csv_report::DEFAULTED_FIELDS,
+            Self::Trufflehog => trufflehog_report::DEFAULTED_FIELDS,
+            Self::DetectSecrets => detect_secrets_report::DEFAULTED_FIELDS,
+        }
+    }
+}
+
+// Compression of report files, detected from the last file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Some(Self::Gzip),
+            Some("zst") => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+
+    pub fn compress_file(self, src: &Path, dst: &Path) -> anyhow::Result<()> {
+        let mut reader = BufReader::new(
+            File::open(src).with_context(|| format!("Failed to open {}", src.display()))?,
+        );
+        let writer =
+            File::create(dst).with_context(|| format!("Failed to create {}", dst.display()))?;
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(writer, GzipLevel::default());
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?;
+            }
+            Self::Zstd => {
+                let mut encoder = ZstdEncoder::new(writer, 0)?;
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// `repo.json.gz` to `repo.json`. Paths without compression extension are returned as is.
+pub(in crate::report) fn strip_compression(path: &Path) -> PathBuf {
+    if Compression::from_path(path).is_some() {
+        path.with_extension("")
+    } else {
+        path.to_owned()
+    }
+}
+
+// Path to read a report from stdin instead of a file.
+pub const STDIN_PATH: &str = "-";
+
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new(STDIN_PATH)
+}
+
+pub fn open_report(path: &Path) -> anyhow::Result<Box<dyn Read>> {
+    if is_stdin(path) {
+        return Ok(Box::new(stdin().lock()));
+    }
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let reader: Box<dyn Read> = match Compression::from_path(path) {
+        None => Box::new(file),
+        Some(Compression::Gzip) => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        Some(Compression::Zstd) => Box::new(ZstdDecoder::new(file)?),
+    };
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Json => "JSON",
+            Self::Sarif => "SARIF",
+            Self::Csv => "CSV",
+            Self::Trufflehog => "TruffleHog",
+            Self::DetectSecrets => "detect-secrets",
+        };
+        write!(f, "{s}")
+    }
+}
+
+pub fn read_report(path: &Path, format: ReportFormat) -> anyhow::Result<Report> {
+    let mut findings = Vec::new();
+    for_each_finding(path, format, |finding| findings.push(finding))?;
+    let metadata = read_metadata(path)?;
+    Ok(Report {
+        repo_name: metadata
+            .as_ref()
+            .map_or_else(|| file_stem_name(path), ReportMetadata::full_name),
+        metadata,
+        findings,
+    })
+}
+
+// Prefer the name in the metadata sidecar, file names don't tell orgs and can be renamed.
+pub fn repo_name(path: &Path) -> anyhow::Result<String> {
+    Ok(read_metadata(path)?.map_or_else(|| file_stem_name(path), |m| m.full_name()))
+}
+
+// Reports in nested directories, e.g. `reports/<org>/<repo>.json`, are named by the path relative to
+// `base` like `<org>/<repo>`, unless the metadata tells the name.
+pub fn nested_repo_name(path: &Path, base: &Path) -> anyhow::Result<String> {
+    if let Some(metadata) = read_metadata(path)? {
+        return Ok(metadata.full_name());
+    }
+    let mut names: Vec<String> = path
+        .strip_prefix(base)
+        .ok()
+        .and_then(Path::parent)
+        .into_iter()
+        .flat_map(Path::components)
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    names.push(file_stem_name(path));
+    Ok(names.join("/"))
+}
+
+fn file_stem_name(path: &Path) -> String {
+    strip_compression(path)
         .file_stem()
         .unwrap_or_default()
         .to_str()
         .unwrap_or_default()
-        .to_owned();
-    let contents = read_to_string(path)?;
-    let findings = serde_json::from_str(&contents)
-        .with_context(|| format!("Failed to parse json file: {}", path.display()))?;
-    Ok(Report {
-        repo_name,
-        findings,
-    })
+        .to_owned()
+}
+
+// Read findings one by one without holding the whole report in memory, reports of large
+// repositories can be gigabytes. SARIF reports are read at once because findings are nested in runs.
+pub fn for_each_finding<F>(path: &Path, format: ReportFormat, mut f: F) -> anyhow::Result<()>
+where
+    F: FnMut(Finding),
+{
+    let reader = open_report(path)?;
+    let mut unknown_fields = BTreeSet::new();
+    let mut f = |finding: Finding| {
+        unknown_fields.extend(finding.extra.keys().cloned());
+        f(finding);
+    };
+    match format {
+        ReportFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_reader(reader);
+            deserializer
+                .deserialize_seq(FindingsVisitor(&mut f))
+                .and_then(|()| deserializer.end())
+                .map_err(anyhow::Error::from)
+        }
+        ReportFormat::Sarif => {
+            sarif_report::parse(reader).map(|findings| findings.into_iter().for_each(f))
+        }
+        ReportFormat::Csv => csv_report::parse(reader, f),
+        ReportFormat::Trufflehog => trufflehog_report::parse(reader, f),
+        ReportFormat::DetectSecrets => {
+            detect_secrets_report::parse(reader).map(|findings| findings.into_iter().for_each(f))
+        }
+    }
+    .with_context(|| format!("Failed to parse {format} file: {}", path.display()))?;
+    warn_defaulted_fields(format);
+    warn_unknown_fields(unknown_fields);
+    Ok(())
+}
+
+struct FindingsVisitor<F>(F);
+
+impl<'de, F> Visitor<'de> for FindingsVisitor<F>
+where
+    F: FnMut(Finding),
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of gitleaks findings")
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        while let Some(finding) = seq.next_element()? {
+            (self.0)(finding);
+        }
+        Ok(())
+    }
+}
+
+// Same as gitleaks: https://github.com/gitleaks/gitleaks/blob/v8.21.3/detect/detect.go#L392-L397
+fn fingerprint(commit: &str, file: &str, rule_id: &str, start_line: usize) -> String {
+    if commit.is_empty() {
+        format!("{file}:{rule_id}:{start_line}")
+    } else {
+        format!("{commit}:{file}:{rule_id}:{start_line}")
+    }
+}
+
+fn warn_defaulted_fields(format: ReportFormat) {
+    static WARNED: Once = Once::new();
+    let fields = format.defaulted_fields();
+    if !fields.is_empty() {
+        WARNED.call_once(|| {
+            eprintln!(
+                "Warning: {format} reports don't have some fields, defaulted: {}",
+                fields.join(", ")
+            );
+        });
+    }
+}
+
+// Warn only once even if multiple reports are read, listing unknown fields of the first report having them.
+fn warn_unknown_fields(unknown_fields: BTreeSet<String>) {
+    static WARNED: Once = Once::new();
+    if !unknown_fields.is_empty() {
+        WARNED.call_once(|| {
+            eprintln!(
+                "Warning: unknown fields found in gitleaks report, possibly added by a newer gitleaks version: {}",
+                unknown_fields.into_iter().collect::<Vec<_>>().join(", ")
+            );
+        });
+    }
 }
 
 pub struct Report {
     pub repo_name: String,
+    pub metadata: Option<ReportMetadata>,
     pub findings: Vec<Finding>,
 }
 
@@ -36,7 +316,7 @@ pub struct AllowedFinding {
 
 // https://github.com/gitleaks/gitleaks/blob/v8.18.0/report/finding.go#L9-L43
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "PascalCase", deny_unknown_fields)]
+#[serde(rename_all = "PascalCase")]
 pub struct Finding {
     pub description: String,
     pub start_line: usize,
@@ -44,8 +324,10 @@ pub struct Finding {
     pub start_column: usize,
     pub end_column: usize,
 
-    // This is generated by patched version of gitleaks.
-    pub line: String,
+    // This is generated by patched version of gitleaks, or by the gls report template. Reports from
+    // vanilla gitleaks don't have this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<String>,
 
     #[serde(rename = "Match")]
     pub matched: String,
@@ -68,6 +350,14 @@ pub struct Finding {
     pub rule_id: String,
 
     pub fingerprint: String,
+
+    // Scanner which reported this finding when it's not gitleaks, e.g. `trufflehog`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scanner: Option<String>,
+
+    // Fields added by newer gitleaks versions, e.g. `Link`. Kept as is to round-trip them.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
 impl PartialEq for Finding {
@@ -86,7 +376,7 @@ impl Hash for Finding {
 
 impl Finding {
     pub fn line_in_length(&self, length: usize) -> String {
-        string_in_length(&self.line, length)
+        string_in_length(self.line.as_deref().unwrap_or_default(), length)
     }
 
     pub fn file_in_length(&self, length: usize) -> String {
@@ -99,7 +389,7 @@ impl Finding {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "PascalCase", deny_unknown_fields)]
+#[serde(rename_all = "PascalCase")]
 pub struct FindingWithoutLine {
     pub description: String,
     pub start_line: usize,
@@ -121,6 +411,10 @@ pub struct FindingWithoutLine {
     #[serde(rename = "RuleID")]
     pub rule_id: String,
     pub fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scanner: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
 impl From<Finding> for FindingWithoutLine {
@@ -144,6 +438,8 @@ impl From<Finding> for FindingWithoutLine {
             tags: item.tags,
             rule_id: item.rule_id,
             fingerprint: item.fingerprint,
+            scanner: item.scanner,
+            extra: item.extra,
         }
     }
 }
@@ -179,7 +475,7 @@ pub mod test {
             end_line: 1,
             start_column: 1,
             end_column: 1,
-            line,
+            line: Some(line),
             matched,
             secret,
             file: "test-file".to_owned(),
@@ -193,6 +489,99 @@ pub mod test {
             tags: vec![],
             fingerprint: "test-fingerprint".to_owned(),
             rule_id: "test-rule".to_owned(),
+            scanner: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    #[test]
+    fn test_for_each_finding_json() -> anyhow::Result<()> {
+        let findings = vec![test::build_empty_finding(), test::build_empty_finding()];
+        let mut file = NamedTempFile::new()?;
+        serde_json::to_writer(&mut file, &findings)?;
+        file.flush()?;
+
+        let mut count = 0;
+        for_each_finding(file.path(), ReportFormat::Json, |_| count += 1)?;
+        assert_eq!(count, 2);
+
+        writeln!(file, "trailing")?;
+        file.flush()?;
+        assert!(for_each_finding(file.path(), ReportFormat::Json, |_| ()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_reports() -> anyhow::Result<()> {
+        let findings = vec![test::build_empty_finding(), test::build_empty_finding()];
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("repo.json");
+        serde_json::to_writer(File::create(&path)?, &findings)?;
+
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let compressed_path = dir
+                .path()
+                .join(format!("repo.json.{}", compression.extension()));
+            compression.compress_file(&path, &compressed_path)?;
+
+            assert_eq!(Compression::from_path(&compressed_path), Some(compression));
+            assert_eq!(
+                ReportFormat::from_path(&compressed_path),
+                Some(ReportFormat::Json)
+            );
+            let report = read_report(&compressed_path, ReportFormat::Json)?;
+            assert_eq!(report.repo_name, "repo");
+            assert_eq!(report.findings.len(), 2);
         }
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_repo_name() -> anyhow::Result<()> {
+        let base = Path::new("reports");
+        assert_eq!(
+            nested_repo_name(Path::new("reports/org/repo.json.gz"), base)?,
+            "org/repo"
+        );
+        assert_eq!(
+            nested_repo_name(Path::new("reports/repo.json"), base)?,
+            "repo"
+        );
+        assert_eq!(
+            nested_repo_name(Path::new("other/repo.json"), base)?,
+            "repo"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_fields_round_trip() -> anyhow::Result<()> {
+        let mut finding = serde_json::to_value(test::build_empty_finding())?;
+        let object = finding.as_object_mut().unwrap();
+        object.insert("Link".to_owned(), Value::from("https://example.com"));
+        object.remove("Line");
+
+        let finding: Finding = serde_json::from_value(finding)?;
+        assert!(finding.line.is_none());
+        assert_eq!(
+            finding.extra.get("Link"),
+            Some(&Value::from("https://example.com"))
+        );
+
+        let without_line = serde_json::to_value(FindingWithoutLine::from(finding))?;
+        assert_eq!(
+            without_line.get("Link"),
+            Some(&Value::from("https://example.com"))
+        );
+        Ok(())
     }
 }
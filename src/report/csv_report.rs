@@ -0,0 +1,116 @@
+use std::{collections::BTreeMap, io::Read};
+
+use anyhow::Context as _;
+use csv::ReaderBuilder;
+use serde::Deserialize;
+
+use crate::report::{Finding, fingerprint};
+
+// Row of `gitleaks --report-format csv`.
+// https://github.com/gitleaks/gitleaks/blob/v8.21.3/report/csv.go
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Row {
+    #[serde(rename = "RuleID")]
+    rule_id: String,
+    #[serde(default)]
+    commit: String,
+    file: String,
+    #[serde(default)]
+    symlink_file: String,
+    secret: String,
+    #[serde(rename = "Match")]
+    matched: String,
+    start_line: usize,
+    end_line: usize,
+    start_column: usize,
+    end_column: usize,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    date: String,
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    fingerprint: String,
+    // Space separated. Old gitleaks versions don't have this column.
+    #[serde(default)]
+    tags: String,
+}
+
+// CSV reports don't have these fields, they are left empty.
+pub const DEFAULTED_FIELDS: &[&str] = &["Description", "Entropy", "Line"];
+
+pub fn parse<R, F>(reader: R, mut f: F) -> anyhow::Result<()>
+where
+    R: Read,
+    F: FnMut(Finding),
+{
+    for (i, row) in ReaderBuilder::new()
+        .from_reader(reader)
+        .deserialize::<Row>()
+        .enumerate()
+    {
+        // Header is the first line.
+        let row = row.with_context(|| format!("Failed to parse CSV record {}", i + 1))?;
+        f(to_finding(row));
+    }
+    Ok(())
+}
+
+fn to_finding(row: Row) -> Finding {
+    let fingerprint = if row.fingerprint.is_empty() {
+        fingerprint(&row.commit, &row.file, &row.rule_id, row.start_line)
+    } else {
+        row.fingerprint
+    };
+    Finding {
+        description: String::new(),
+        start_line: row.start_line,
+        end_line: row.end_line,
+        start_column: row.start_column,
+        end_column: row.end_column,
+        line: None,
+        matched: row.matched,
+        secret: row.secret,
+        file: row.file,
+        symlink_file: row.symlink_file,
+        commit: row.commit,
+        entropy: 0.0,
+        author: row.author,
+        email: row.email,
+        date: row.date,
+        message: row.message,
+        tags: row.tags.split_whitespace().map(ToOwned::to_owned).collect(),
+        rule_id: row.rule_id,
+        fingerprint,
+        scanner: None,
+        extra: BTreeMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let contents = "RuleID,Commit,File,SymlinkFile,Secret,Match,StartLine,EndLine,StartColumn,EndColumn,Author,Message,Date,Email,Fingerprint,Tags
+test,abc,secret.txt,,deadbeef,\"secret_key = deadbeef\",2,2,1,21,a,\"multi
+line\",d,e,abc:secret.txt:test:2,t1 t2
+";
+        let mut findings = Vec::new();
+        parse(contents.as_bytes(), |finding| findings.push(finding))?;
+        assert_eq!(findings.len(), 1);
+        let finding = findings.first().unwrap();
+        assert_eq!(finding.rule_id, "test");
+        assert_eq!(finding.matched, "secret_key = deadbeef");
+        assert_eq!(finding.message, "multi\nline");
+        assert_eq!(finding.start_line, 2);
+        assert_eq!(finding.tags, vec!["t1", "t2"]);
+        assert_eq!(finding.fingerprint, "abc:secret.txt:test:2");
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Read,
+};
+
+use serde::Deserialize;
+
+use crate::report::{Finding, fingerprint};
+
+pub const SCANNER: &str = "detect-secrets";
+
+// Output of `detect-secrets scan`, the same format as baseline files.
+// https://github.com/Yelp/detect-secrets/blob/v1.5.0/docs/baseline.md
+#[derive(Debug, Deserialize)]
+struct Root {
+    #[serde(default)]
+    results: HashMap<String, Vec<PotentialSecret>>,
+}
+
+#[derive(Debug, Deserialize)]
+// `PotentialSecret` in detect-secrets.
+struct PotentialSecret {
+    #[serde(rename = "type")]
+    secret_type: String,
+    filename: String,
+    hashed_secret: String,
+    #[serde(default)]
+    is_verified: bool,
+    #[serde(default)]
+    line_number: usize,
+}
+
+// detect-secrets doesn't report secret values, `Secret` and `Match` are the SHA-1 hashed secret.
+pub const DEFAULTED_FIELDS: &[&str] = &[
+    "Secret",
+    "Match",
+    "StartColumn",
+    "EndColumn",
+    "Line",
+    "SymlinkFile",
+    "Commit",
+    "Entropy",
+    "Author",
+    "Email",
+    "Date",
+    "Message",
+];
+
+// Results are grouped by files in a single object, so read them at once.
+pub fn parse<R: Read>(reader: R) -> anyhow::Result<Vec<Finding>> {
+    let root: Root = serde_json::from_reader(reader)?;
+    let mut findings = root
+        .results
+        .into_values()
+        .flatten()
+        .map(to_finding)
+        .collect::<Vec<_>>();
+    // `results` is a map, keep the output stable.
+    findings.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    Ok(findings)
+}
+
+fn to_finding(secret: PotentialSecret) -> Finding {
+    let tags = if secret.is_verified {
+        vec!["verified".to_owned()]
+    } else {
+        vec![]
+    };
+    Finding {
+        description: format!("{} detected by detect-secrets", secret.secret_type),
+        start_line: secret.line_number,
+        end_line: secret.line_number,
+        start_column: 0,
+        end_column: 0,
+        line: None,
+        matched: secret.hashed_secret.clone(),
+        secret: secret.hashed_secret,
+        symlink_file: String::new(),
+        commit: String::new(),
+        entropy: 0.0,
+        author: String::new(),
+        email: String::new(),
+        date: String::new(),
+        message: String::new(),
+        tags,
+        fingerprint: fingerprint(
+            "",
+            &secret.filename,
+            &secret.secret_type,
+            secret.line_number,
+        ),
+        file: secret.filename,
+        rule_id: secret.secret_type,
+        scanner: Some(SCANNER.to_owned()),
+        extra: BTreeMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let contents = r#"{
+  "version": "1.5.0",
+  "plugins_used": [{"name": "AWSKeyDetector"}],
+  "filters_used": [],
+  "results": {
+    "secret.txt": [
+      {"type": "Secret Keyword", "filename": "secret.txt", "hashed_secret": "0123abcd", "is_verified": false, "line_number": 2}
+    ],
+    "a.py": [
+      {"type": "AWS Access Key", "filename": "a.py", "hashed_secret": "4567cdef", "is_verified": true, "line_number": 10}
+    ]
+  },
+  "generated_at": "2024-01-01T00:00:00Z"
+}"#;
+        let findings = parse(contents.as_bytes())?;
+        assert_eq!(findings.len(), 2);
+
+        let finding = findings.first().unwrap();
+        assert_eq!(finding.file, "a.py");
+        assert_eq!(finding.rule_id, "AWS Access Key");
+        assert_eq!(finding.tags, vec!["verified"]);
+
+        let finding = findings.last().unwrap();
+        assert_eq!(finding.secret, "0123abcd");
+        assert_eq!(finding.fingerprint, "secret.txt:Secret Keyword:2");
+        assert_eq!(finding.scanner.as_deref(), Some(SCANNER));
+        Ok(())
+    }
+}
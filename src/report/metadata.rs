@@ -0,0 +1,110 @@
+use std::{
+    fs::{File, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::report::strip_compression;
+
+const METADATA_SUFFIX: &str = ".meta.json";
+
+// Scan context written by `scan` next to each report, e.g. `repo.meta.json` for `repo.json` or
+// `repo.json.gz`. Everything except the repository name is optional since not all of it is
+// available in every environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReportMetadata {
+    pub repo_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitleaks_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scanned_at: Option<DateTime<Utc>>,
+}
+
+impl ReportMetadata {
+    // Qualify with the org so that same named repositories in different orgs don't collide.
+    pub fn full_name(&self) -> String {
+        self.org.as_ref().map_or_else(
+            || self.repo_name.clone(),
+            |org| format!("{org}/{}", self.repo_name),
+        )
+    }
+}
+
+pub fn metadata_path(report_path: &Path) -> PathBuf {
+    let path = strip_compression(report_path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}{METADATA_SUFFIX}"))
+}
+
+pub fn is_metadata_path(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(METADATA_SUFFIX))
+}
+
+// Reports without a sidecar, e.g. from older versions or other tools, have no metadata.
+pub fn read_metadata(report_path: &Path) -> anyhow::Result<Option<ReportMetadata>> {
+    let path = metadata_path(report_path);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let contents =
+        read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let metadata = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse report metadata: {}", path.display()))?;
+    Ok(Some(metadata))
+}
+
+pub fn write_metadata(report_path: &Path, metadata: &ReportMetadata) -> anyhow::Result<()> {
+    let path = metadata_path(report_path);
+    let file =
+        File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    serde_json::to_writer_pretty(file, metadata)
+        .with_context(|| format!("Failed to write report metadata: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_path() {
+        for report in ["dir/repo.json", "dir/repo.json.gz", "dir/repo.sarif"] {
+            assert_eq!(
+                metadata_path(Path::new(report)),
+                Path::new("dir/repo.meta.json")
+            );
+        }
+        assert!(is_metadata_path(Path::new("dir/repo.meta.json")));
+        assert!(!is_metadata_path(Path::new("dir/repo.json")));
+    }
+
+    #[test]
+    fn test_read_write_metadata() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let report_path = dir.path().join("repo.json");
+        assert_eq!(read_metadata(&report_path)?, None);
+
+        let metadata = ReportMetadata {
+            repo_name: "repo".to_owned(),
+            org: Some("org".to_owned()),
+            ..Default::default()
+        };
+        write_metadata(&report_path, &metadata)?;
+        let read = read_metadata(&report_path)?.unwrap();
+        assert_eq!(read, metadata);
+        assert_eq!(read.full_name(), "org/repo");
+        Ok(())
+    }
+}
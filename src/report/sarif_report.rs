@@ -0,0 +1,236 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Read,
+    sync::Once,
+};
+
+use serde::Deserialize;
+
+use crate::report::{Finding, fingerprint};
+
+// Subset of SARIF written by `gitleaks --report-format sarif`.
+// https://github.com/gitleaks/gitleaks/blob/v8.21.3/report/sarif.go
+#[derive(Debug, Deserialize)]
+struct Root {
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Run {
+    tool: Tool,
+    #[serde(default)]
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Debug, Deserialize)]
+struct Driver {
+    #[serde(default)]
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    short_description: Option<Text>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Text {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    #[serde(default)]
+    locations: Vec<Location>,
+    #[serde(default)]
+    partial_fingerprints: PartialFingerprints,
+    #[serde(default)]
+    properties: Properties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    #[serde(default)]
+    start_column: usize,
+    end_line: Option<usize>,
+    #[serde(default)]
+    end_column: usize,
+    snippet: Option<Text>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PartialFingerprints {
+    commit_sha: String,
+    commit_message: String,
+    email: String,
+    author: String,
+    date: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Properties {
+    tags: Vec<String>,
+}
+
+// SARIF reports don't have these fields. `Match` is defaulted to the secret, `Fingerprint` is
+// computed in the same way as gitleaks, others are left empty.
+pub const DEFAULTED_FIELDS: &[&str] = &["Match", "SymlinkFile", "Entropy", "Line", "Fingerprint"];
+
+pub fn parse<R: Read>(reader: R) -> anyhow::Result<Vec<Finding>> {
+    let root: Root = serde_json::from_reader(reader)?;
+    let mut findings = Vec::new();
+    let mut skipped = 0;
+    for run in root.runs {
+        let descriptions = run
+            .tool
+            .driver
+            .rules
+            .into_iter()
+            .filter_map(|rule| rule.short_description.map(|d| (rule.id, d.text)))
+            .collect::<HashMap<_, _>>();
+        for result in run.results {
+            match to_finding(result, &descriptions) {
+                Some(finding) => findings.push(finding),
+                None => skipped += 1,
+            }
+        }
+    }
+    warn_skipped_results(skipped);
+    Ok(findings)
+}
+
+// Warn only once even if multiple reports are read, counting results of the first report having them.
+fn warn_skipped_results(skipped: usize) {
+    static WARNED: Once = Once::new();
+    if skipped > 0 {
+        WARNED.call_once(|| {
+            eprintln!("Warning: {skipped} SARIF results without locations are skipped");
+        });
+    }
+}
+
+// gitleaks always writes exactly one location, results without it are skipped with a warning.
+fn to_finding(result: SarifResult, descriptions: &HashMap<String, String>) -> Option<Finding> {
+    let location = result.locations.into_iter().next()?.physical_location;
+    let region = location.region;
+    let secret = region.snippet.map(|s| s.text).unwrap_or_default();
+    let file = location.artifact_location.uri;
+    let commit = result.partial_fingerprints.commit_sha;
+    Some(Finding {
+        description: descriptions
+            .get(&result.rule_id)
+            .cloned()
+            .unwrap_or_default(),
+        start_line: region.start_line,
+        end_line: region.end_line.unwrap_or(region.start_line),
+        start_column: region.start_column,
+        end_column: region.end_column,
+        line: None,
+        matched: secret.clone(),
+        secret,
+        fingerprint: fingerprint(&commit, &file, &result.rule_id, region.start_line),
+        file,
+        symlink_file: String::new(),
+        commit,
+        entropy: 0.0,
+        author: result.partial_fingerprints.author,
+        email: result.partial_fingerprints.email,
+        date: result.partial_fingerprints.date,
+        message: result.partial_fingerprints.commit_message,
+        tags: result.properties.tags,
+        rule_id: result.rule_id,
+        scanner: None,
+        extra: BTreeMap::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let contents = r#"{
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {"driver": {"name": "gitleaks", "semanticVersion": "v8.0.0", "informationUri": "https://github.com/gitleaks/gitleaks",
+                    "rules": [{"id": "test", "shortDescription": {"text": "test rule"}}]}},
+                "results": [{
+                    "message": {"text": "test has detected secret for file secret.txt at commit abc."},
+                    "ruleId": "test",
+                    "locations": [{"physicalLocation": {
+                        "artifactLocation": {"uri": "secret.txt"},
+                        "region": {"startLine": 2, "startColumn": 1, "endLine": 2, "endColumn": 21, "snippet": {"text": "deadbeef"}}
+                    }}],
+                    "partialFingerprints": {"commitSha": "abc", "email": "e", "author": "a", "date": "d", "commitMessage": "m"},
+                    "properties": {"tags": ["t"]}
+                }]
+            }]
+        }"#;
+        let findings = parse(contents.as_bytes())?;
+        assert_eq!(findings.len(), 1);
+        let finding = findings.first().unwrap();
+        assert_eq!(finding.rule_id, "test");
+        assert_eq!(finding.description, "test rule");
+        assert_eq!(finding.secret, "deadbeef");
+        assert_eq!(finding.matched, "deadbeef");
+        assert_eq!(finding.file, "secret.txt");
+        assert_eq!(finding.commit, "abc");
+        assert_eq!(finding.message, "m");
+        assert_eq!(finding.tags, vec!["t"]);
+        assert_eq!(finding.fingerprint, "abc:secret.txt:test:2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_without_locations() -> anyhow::Result<()> {
+        let contents = r#"{
+            "runs": [{
+                "tool": {"driver": {"name": "gitleaks"}},
+                "results": [
+                    {"ruleId": "test"},
+                    {"ruleId": "test", "locations": [{"physicalLocation": {
+                        "artifactLocation": {"uri": "secret.txt"},
+                        "region": {"startLine": 2}
+                    }}]}
+                ]
+            }]
+        }"#;
+        let findings = parse(contents.as_bytes())?;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings.first().unwrap().file, "secret.txt");
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use std::{collections::BTreeMap, io::Read, sync::Once};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use serde_json::Deserializer;
+
+use crate::report::{Finding, fingerprint};
+
+pub const SCANNER: &str = "trufflehog";
+
+// Line of `trufflehog --json`, one JSON object per line.
+// https://github.com/trufflesecurity/trufflehog/blob/v3.82.13/pkg/output/json.go
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Line {
+    source_metadata: SourceMetadata,
+    detector_name: String,
+    #[serde(default)]
+    verified: bool,
+    #[serde(default)]
+    raw: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SourceMetadata {
+    data: SourceData,
+}
+
+// Only sources with files are supported, others like S3 buckets don't map to gitleaks findings and
+// are skipped with a warning.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SourceData {
+    git: Option<Location>,
+    filesystem: Option<Location>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Location {
+    #[serde(default)]
+    commit: String,
+    #[serde(default)]
+    file: String,
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    line: usize,
+}
+
+// TruffleHog reports don't have these fields, they are left empty.
+pub const DEFAULTED_FIELDS: &[&str] = &[
+    "Match",
+    "StartColumn",
+    "EndColumn",
+    "Line",
+    "SymlinkFile",
+    "Entropy",
+    "Author",
+    "Message",
+];
+
+pub fn parse<R, F>(reader: R, mut f: F) -> anyhow::Result<()>
+where
+    R: Read,
+    F: FnMut(Finding),
+{
+    let mut skipped = 0;
+    for (i, line) in Deserializer::from_reader(reader)
+        .into_iter::<Line>()
+        .enumerate()
+    {
+        let line = line.with_context(|| format!("Failed to parse TruffleHog result {}", i + 1))?;
+        match to_finding(line) {
+            Some(finding) => f(finding),
+            None => skipped += 1,
+        }
+    }
+    warn_skipped_results(skipped);
+    Ok(())
+}
+
+// Warn only once even if multiple reports are read, counting results of the first report having them.
+fn warn_skipped_results(skipped: usize) {
+    static WARNED: Once = Once::new();
+    if skipped > 0 {
+        WARNED.call_once(|| {
+            eprintln!("Warning: {skipped} TruffleHog results of sources without files are skipped");
+        });
+    }
+}
+
+fn to_finding(line: Line) -> Option<Finding> {
+    let location = line
+        .source_metadata
+        .data
+        .git
+        .or(line.source_metadata.data.filesystem)?;
+    let rule_id = line.detector_name;
+    let tags = if line.verified {
+        vec!["verified".to_owned()]
+    } else {
+        vec![]
+    };
+    Some(Finding {
+        description: format!("{rule_id} secret detected by TruffleHog"),
+        start_line: location.line,
+        end_line: location.line,
+        start_column: 0,
+        end_column: 0,
+        line: None,
+        matched: line.raw.clone(),
+        secret: line.raw,
+        symlink_file: String::new(),
+        entropy: 0.0,
+        author: String::new(),
+        email: location.email,
+        date: location.timestamp,
+        message: String::new(),
+        tags,
+        fingerprint: fingerprint(&location.commit, &location.file, &rule_id, location.line),
+        file: location.file,
+        commit: location.commit,
+        rule_id,
+        scanner: Some(SCANNER.to_owned()),
+        extra: BTreeMap::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let contents = r#"{"SourceMetadata":{"Data":{"Git":{"commit":"abc","file":"secret.txt","email":"a <a@example.com>","repository":"https://example.com/repo.git","timestamp":"2024-01-01 00:00:00 +0000","line":2}}},"SourceID":1,"SourceType":16,"SourceName":"trufflehog - git","DetectorType":2,"DetectorName":"AWS","DecoderName":"PLAIN","Verified":true,"Raw":"AKIAEXAMPLE","Redacted":"AKIA","ExtraData":null}
+{"SourceMetadata":{"Data":{"Filesystem":{"file":"config.yml","line":5}}},"DetectorName":"Github","Verified":false,"Raw":"ghp_example"}
+{"SourceMetadata":{"Data":{"S3":{"bucket":"example","file":"secret.txt"}}},"DetectorName":"AWS","Raw":"AKIAEXAMPLE"}
+"#;
+        let mut findings = Vec::new();
+        parse(contents.as_bytes(), |finding| findings.push(finding))?;
+        assert_eq!(findings.len(), 2);
+
+        let finding = findings.first().unwrap();
+        assert_eq!(finding.rule_id, "AWS");
+        assert_eq!(finding.secret, "AKIAEXAMPLE");
+        assert_eq!(finding.start_line, 2);
+        assert_eq!(finding.tags, vec!["verified"]);
+        assert_eq!(finding.fingerprint, "abc:secret.txt:AWS:2");
+        assert_eq!(finding.scanner.as_deref(), Some(SCANNER));
+
+        let finding = findings.last().unwrap();
+        assert_eq!(finding.file, "config.yml");
+        assert_eq!(finding.fingerprint, "config.yml:Github:5");
+        Ok(())
+    }
+}
@@ -1,9 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use anyhow::Context;
 use serde::Serialize;
+use sha2::{Digest as _, Sha256};
 
-use crate::report::Finding;
+use crate::{
+    config::Allowlist,
+    gitleaks_config,
+    redact::{Redaction, hmac_sha256},
+    report::{AllowedFinding, Finding},
+    severity::{Severities, Severity},
+};
 
 #[derive(Debug, Serialize)]
 struct Root {
@@ -35,16 +42,23 @@ struct Driver {
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct Rule {
     id: String,
     name: String,
-    #[serde(rename = "shortDescription")]
-    short_description: ShortDescription,
+    short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<Message>,
+    default_configuration: DefaultConfiguration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<Properties>,
 }
 
 #[derive(Debug, Serialize)]
-struct ShortDescription {
-    text: String,
+struct DefaultConfiguration {
+    level: &'static str,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,9 +66,20 @@ struct ShortDescription {
 struct SarifResult {
     message: Message,
     rule_id: String,
+    rule_index: usize,
+    level: &'static str,
     locations: Vec<Location>,
     partial_fingerprints: PartialFingerprints,
-    properties: Properties,
+    properties: ResultProperties,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suppressions: Vec<Suppression>,
+}
+
+// Allowed findings are results suppressed by allowlists, which are outside of the SARIF log.
+#[derive(Debug, Serialize)]
+struct Suppression {
+    kind: &'static str,
+    justification: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -95,9 +120,28 @@ struct Snippet {
     text: String,
 }
 
+// Stable identities of findings, used by code scanning to track alerts across runs. gitleaks
+// fingerprints contain commits and lines, so the secret hash also matches moved secrets.
 #[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
 struct PartialFingerprints {
+    #[serde(
+        rename = "gitleaksFingerprint/v1",
+        skip_serializing_if = "String::is_empty"
+    )]
+    gitleaks_fingerprint: String,
+    #[serde(rename = "secretHash/v1", skip_serializing_if = "Option::is_none")]
+    secret_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Properties {
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultProperties {
+    tags: Vec<String>,
     commit_sha: String,
     commit_message: String,
     email: String,
@@ -105,13 +149,63 @@ struct PartialFingerprints {
     date: String,
 }
 
-#[derive(Debug, Serialize)]
-struct Properties {
-    tags: Vec<String>,
+const fn to_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// How `secretHash/v1` is computed. It's derived from the secret, so plain hashes in redacted
+// outputs would let anyone test guessed secrets against them.
+#[derive(Debug)]
+pub enum SecretHash {
+    Plain,
+    // HMAC-SHA256 with a user supplied key.
+    Keyed(Vec<u8>),
+    Omitted,
+}
+
+impl SecretHash {
+    // The key is used whenever given, so hashes don't change when `--redact` is added later.
+    pub fn new(redaction: &Redaction, key: Option<&str>) -> Self {
+        match key {
+            Some(key) => Self::Keyed(key.as_bytes().to_vec()),
+            None if *redaction == Redaction::None => Self::Plain,
+            None => Self::Omitted,
+        }
+    }
+
+    // Line-independent, and hashed before redaction so that redacted outputs keep the same hashes.
+    fn hash(&self, finding: &Finding) -> Option<String> {
+        let message = format!("{}\0{}\0{}", finding.rule_id, finding.file, finding.secret);
+        match self {
+            Self::Plain => Some(format!("{:x}", Sha256::digest(message))),
+            Self::Keyed(key) => Some(hmac_sha256(key, message.as_bytes())),
+            Self::Omitted => None,
+        }
+    }
 }
 
-fn to_result(finding: Finding, guide: &str) -> SarifResult {
+fn to_result(
+    finding: Finding,
+    rule_indices: &HashMap<String, usize>,
+    severities: &Severities,
+    guide: &str,
+    redaction: &Redaction,
+    secret_hash: &SecretHash,
+) -> SarifResult {
+    let level = to_level(severities.severity(&finding));
+    let secret_hash = secret_hash.hash(&finding);
+    let rule_index = rule_indices
+        .get(&finding.rule_id)
+        .copied()
+        .unwrap_or_default();
+    let finding = redaction.redact_finding(finding);
     SarifResult {
+        level,
+        rule_index,
         message: Message {
             text: format!(
                 "`{}` rule finds possible secret in {}: `{}`{guide}",
@@ -134,13 +228,91 @@ fn to_result(finding: Finding, guide: &str) -> SarifResult {
             },
         }],
         partial_fingerprints: PartialFingerprints {
+            gitleaks_fingerprint: finding.fingerprint,
+            secret_hash,
+        },
+        properties: ResultProperties {
+            tags: finding.tags,
             commit_sha: finding.commit,
             commit_message: finding.message,
             email: finding.email,
             author: finding.author,
             date: finding.date,
         },
-        properties: Properties { tags: finding.tags },
+        suppressions: Vec::new(),
+    }
+}
+
+fn to_suppressed_result(
+    allowed_finding: AllowedFinding,
+    descriptions: &HashMap<&str, Option<&str>>,
+    rule_indices: &HashMap<String, usize>,
+    severities: &Severities,
+    guide: &str,
+    redaction: &Redaction,
+    secret_hash: &SecretHash,
+) -> SarifResult {
+    let justification = match descriptions.get(allowed_finding.allow_rule_id.as_str()) {
+        Some(Some(description)) => format!("{}: {description}", allowed_finding.allow_rule_id),
+        _ => allowed_finding.allow_rule_id,
+    };
+    SarifResult {
+        suppressions: vec![Suppression {
+            kind: "external",
+            justification,
+        }],
+        ..to_result(
+            allowed_finding.finding,
+            rule_indices,
+            severities,
+            guide,
+            redaction,
+            secret_hash,
+        )
+    }
+}
+
+// Rules are described by the gitleaks config if given, otherwise by their ids and the tags of their
+// findings, so that tag severities are the same as the results.
+fn to_rule(
+    rule_id: &str,
+    finding_tags: &BTreeSet<&str>,
+    gitleaks_rule: Option<&gitleaks_config::Rule>,
+    severities: &Severities,
+    guide: Option<&str>,
+) -> Rule {
+    let description = gitleaks_rule.and_then(|rule| rule.description.as_deref());
+    let tags = gitleaks_rule
+        .and_then(|rule| rule.tags.clone())
+        .unwrap_or_else(|| finding_tags.iter().map(|&tag| tag.to_owned()).collect());
+    let keywords = gitleaks_rule
+        .and_then(|rule| rule.keywords.as_ref())
+        .filter(|keywords| !keywords.is_empty())
+        .map(|keywords| format!("Keywords: {}.", keywords.join(", ")));
+    let full_description = [description.map(ToOwned::to_owned), keywords]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let help = [description, guide]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    Rule {
+        id: rule_id.to_owned(),
+        name: format!("{rule_id} rule"),
+        short_description: Message {
+            text: description.unwrap_or(rule_id).to_owned(),
+        },
+        full_description: (!full_description.is_empty()).then(|| Message {
+            text: full_description.join("\n\n"),
+        }),
+        help: (!help.is_empty()).then(|| Message {
+            text: help.join("\n\n"),
+        }),
+        default_configuration: DefaultConfiguration {
+            level: to_level(severities.rule_severity(rule_id, &tags)),
+        },
+        properties: (!tags.is_empty()).then_some(Properties { tags }),
     }
 }
 
@@ -150,22 +322,84 @@ const DRIVER_NAME: &str = env!("CARGO_PKG_NAME");
 const DRIVER_SEMANTIC_VERSION: &str = env!("CARGO_PKG_VERSION");
 const DRIVER_INFORMATION_URI: &str = env!("CARGO_PKG_HOMEPAGE");
 
-pub fn to_sarif(findings: Vec<Finding>, guide: &str) -> anyhow::Result<String> {
-    let rules: HashSet<&str> = findings.iter().fold(HashSet::new(), |mut acc, finding| {
-        acc.insert(&finding.rule_id);
-        acc
-    });
+// How findings are written to SARIF, besides the findings themselves.
+#[derive(Debug)]
+pub struct SarifOptions<'options> {
+    // Descriptions of allowlists justify suppressed results.
+    pub allowlists: &'options [Allowlist],
+    // Rules of the gitleaks config describe rules, empty without the config.
+    pub gitleaks_rules: &'options [gitleaks_config::Rule],
+    pub severities: &'options Severities,
+    pub guide: Option<&'options str>,
+    pub redaction: &'options Redaction,
+    pub secret_hash: &'options SecretHash,
+}
 
-    let rules = rules
-        .into_iter()
-        .map(|rule_id| Rule {
-            id: rule_id.to_owned(),
-            name: format!("{rule_id} rule"),
-            short_description: ShortDescription {
-                text: rule_id.to_owned(),
-            },
+// Allowed findings are written as suppressed results, justified by their allowlists. Rules are
+// sorted by id so that the same findings always produce the same output.
+pub fn to_sarif(
+    findings: Vec<Finding>,
+    allowed: Vec<AllowedFinding>,
+    options: &SarifOptions,
+) -> anyhow::Result<String> {
+    let SarifOptions {
+        allowlists,
+        gitleaks_rules,
+        severities,
+        guide,
+        redaction,
+        secret_hash,
+    } = *options;
+    let mut rule_tags = BTreeMap::<&str, BTreeSet<&str>>::new();
+    for finding in findings.iter().chain(
+        allowed
+            .iter()
+            .map(|allowed_finding| &allowed_finding.finding),
+    ) {
+        rule_tags
+            .entry(&finding.rule_id)
+            .or_default()
+            .extend(finding.tags.iter().map(String::as_str));
+    }
+
+    let gitleaks_rules = gitleaks_rules
+        .iter()
+        .map(|rule| (rule.id.as_str(), rule))
+        .collect::<HashMap<_, _>>();
+    let rules = rule_tags
+        .iter()
+        .map(|(rule_id, tags)| {
+            let gitleaks_rule = gitleaks_rules.get(rule_id).copied();
+            to_rule(rule_id, tags, gitleaks_rule, severities, guide)
         })
         .collect();
+    let rule_indices = rule_tags
+        .into_keys()
+        .enumerate()
+        .map(|(index, rule_id)| (rule_id.to_owned(), index))
+        .collect::<HashMap<_, _>>();
+
+    let guide = guide.map_or_else(String::new, |guide| format!("\n\n{guide}"));
+
+    let descriptions = allowlists
+        .iter()
+        .map(|allowlist| (allowlist.id.as_str(), allowlist.description.as_deref()))
+        .collect::<HashMap<_, _>>();
+    let results = findings
+        .into_iter()
+        .map(|f| to_result(f, &rule_indices, severities, &guide, redaction, secret_hash))
+        .chain(allowed.into_iter().map(|a| {
+            to_suppressed_result(
+                a,
+                &descriptions,
+                &rule_indices,
+                severities,
+                &guide,
+                redaction,
+                secret_hash,
+            )
+        }))
+        .collect();
 
     let root = Root {
         schema: SCHEMA,
@@ -179,7 +413,7 @@ pub fn to_sarif(findings: Vec<Finding>, guide: &str) -> anyhow::Result<String> {
                     rules,
                 },
             },
-            results: findings.into_iter().map(|f| to_result(f, guide)).collect(),
+            results,
         }],
     };
 
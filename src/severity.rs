@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use anyhow::Context as _;
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::SeverityConfig, report::Finding};
+
+/// Severity of confirmed findings, mapped to levels of each output format.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    ValueEnum,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// `<rule_id>=<severity>` given in CLI options.
+#[derive(Debug, Clone)]
+pub struct RuleSeverity {
+    rule_id: String,
+    severity: Severity,
+}
+
+impl FromStr for RuleSeverity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rule_id, severity) = s
+            .rsplit_once('=')
+            .with_context(|| format!("Expected <rule_id>=<severity>: {s}"))?;
+        let severity = Severity::from_str(severity, true)
+            .map_err(|err| anyhow::anyhow!("Unknown severity in {s}: {err}"))?;
+        Ok(Self {
+            rule_id: rule_id.to_owned(),
+            severity,
+        })
+    }
+}
+
+// Rule ids take precedence over tags, and the highest severity applies when multiple tags match.
+// Findings matching neither get the default, `warning` unless specified.
+#[derive(Debug, Clone)]
+pub struct Severities {
+    by_rule_id: HashMap<String, Severity>,
+    by_tag: HashMap<String, Severity>,
+    default: Severity,
+}
+
+impl Default for Severities {
+    fn default() -> Self {
+        Self {
+            by_rule_id: HashMap::new(),
+            by_tag: HashMap::new(),
+            default: Severity::Warning,
+        }
+    }
+}
+
+impl Severities {
+    // CLI options override the config.
+    pub fn new(config: SeverityConfig, rule_severities: Vec<RuleSeverity>) -> Self {
+        let mut by_rule_id = config.rules.into_iter().collect::<HashMap<_, _>>();
+        by_rule_id.extend(rule_severities.into_iter().map(|r| (r.rule_id, r.severity)));
+        Self {
+            by_rule_id,
+            by_tag: config.tags.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn severity(&self, finding: &Finding) -> Severity {
+        self.rule_severity(&finding.rule_id, &finding.tags)
+    }
+
+    // Findings have tags of their rules, so rules get the same severities as their findings when
+    // given the rule tags, or the tags of their findings.
+    pub fn rule_severity(&self, rule_id: &str, tags: &[String]) -> Severity {
+        if let Some(severity) = self.by_rule_id.get(rule_id) {
+            return *severity;
+        }
+        tags.iter()
+            .filter_map(|tag| self.by_tag.get(tag))
+            .max()
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test::build_empty_finding;
+
+    #[test]
+    fn test_rule_severity() -> anyhow::Result<()> {
+        let severities =
+            Severities::new(SeverityConfig::default(), vec!["test-rule=error".parse()?]);
+        let mut finding = build_empty_finding();
+        assert_eq!(severities.severity(&finding), Severity::Error);
+        finding.rule_id = "other".to_owned();
+        assert_eq!(severities.severity(&finding), Severity::Warning);
+
+        "test-rule".parse::<RuleSeverity>().unwrap_err();
+        "test-rule=fatal".parse::<RuleSeverity>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_severity() -> anyhow::Result<()> {
+        let config = SeverityConfig {
+            rules: [("test-rule".to_owned(), Severity::Info)].into(),
+            tags: [
+                ("low".to_owned(), Severity::Info),
+                ("cloud".to_owned(), Severity::Error),
+            ]
+            .into(),
+        };
+        let severities = Severities::new(config, Vec::new());
+        let mut finding = build_empty_finding();
+        finding.tags = vec!["cloud".to_owned()];
+        assert_eq!(severities.severity(&finding), Severity::Info);
+
+        finding.rule_id = "other".to_owned();
+        assert_eq!(severities.severity(&finding), Severity::Error);
+        finding.tags = vec!["low".to_owned(), "cloud".to_owned()];
+        assert_eq!(severities.severity(&finding), Severity::Error);
+        finding.tags = vec!["unknown".to_owned()];
+        assert_eq!(severities.severity(&finding), Severity::Warning);
+
+        let config = SeverityConfig {
+            rules: [("test-rule".to_owned(), Severity::Info)].into(),
+            ..Default::default()
+        };
+        let severities = Severities::new(config, vec!["test-rule=error".parse()?]);
+        assert_eq!(severities.severity(&build_empty_finding()), Severity::Error);
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ mod setup;
 mod detect {
     use std::{
         collections::HashSet,
-        fs::read_to_string,
+        fs::{File, copy, create_dir_all, read_to_string, write},
         io::{Write as _, stderr, stdout},
         path::Path,
         process::{Command, Output},
@@ -71,9 +71,18 @@ mod detect {
             // Test some findings in `github` format.
             let res = run_apply(empty_allowlist_path, &report_path, "github")?;
             assert!(!res.stdout.is_empty());
-            let expected = indoc! { "
-            ::warning file=secret.txt,line=2,endLine=2,title=Secrets detected::`deadbeef` is considered as secret value.
-            " };
+            // Single line findings have columns, take them from the report of the installed gitleaks.
+            let report = serde_json::from_str::<Value>(&report_body)?;
+            let column = |key: &str| report.pointer(&format!("/0/{key}")).cloned();
+            let (Some(start_column), Some(end_column)) =
+                (column("StartColumn"), column("EndColumn"))
+            else {
+                bail!("missing columns in report: {report_body}");
+            };
+            let expected = format!(
+                "::warning file=secret.txt,line=2,endLine=2,col={start_column},endColumn={end_column},\
+                 title=Secrets detected::`deadbeef` is considered as secret value.\n"
+            );
             assert_eq!(String::from_utf8_lossy(&res.stdout), expected);
         }
         {
@@ -149,6 +158,8 @@ mod detect {
                 HashSet::from([
                     "message",
                     "ruleId",
+                    "ruleIndex",
+                    "level",
                     "locations",
                     "partialFingerprints",
                     "properties"
@@ -158,4 +169,808 @@ mod detect {
 
         Ok(())
     }
+
+    #[test]
+    fn vanilla_report() -> Result<()> {
+        let report_path = Path::new("tests/testdata/vanilla_report.json");
+
+        {
+            // Reports without `Line` field are accepted.
+            let res = run_apply(
+                Path::new("tests/testdata/empty_allowlist.toml"),
+                report_path,
+                "json",
+            )?;
+            let report = from_slice::<Value>(&res.stdout)?;
+            let report = report
+                .as_array()
+                .with_context(|| "report is not an array")?;
+            assert_eq!(report.len(), 1);
+
+            // Unknown fields are preserved with a warning.
+            let finding = report.first().and_then(|r| r.as_object()).unwrap();
+            assert!(finding.get("Link").is_some_and(Value::is_string));
+            assert!(String::from_utf8_lossy(&res.stderr).contains("unknown fields found"));
+        }
+        {
+            // `regexTarget = "line"` falls back to `Match` with a warning.
+            let res = run_apply(
+                Path::new("tests/testdata/line_allowlist.toml"),
+                report_path,
+                "json",
+            )?;
+            assert_eq!(String::from_utf8_lossy(&res.stdout), "[]\n");
+            assert!(String::from_utf8_lossy(&res.stderr).contains("without `Line` field"));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn other_report_formats() -> Result<()> {
+        for report_path in [
+            "tests/testdata/vanilla_report.sarif",
+            "tests/testdata/vanilla_report.csv",
+        ] {
+            let res = run_apply(
+                Path::new("tests/testdata/empty_allowlist.toml"),
+                Path::new(report_path),
+                "json",
+            )?;
+            let report = from_slice::<Value>(&res.stdout)?;
+            let report = report
+                .as_array()
+                .with_context(|| "report is not an array")?;
+            assert_eq!(report.len(), 1, "{report_path}");
+            let finding = report.first().and_then(|r| r.as_object()).unwrap();
+            assert_eq!("deadbeef", finding.get("Secret").unwrap());
+            assert_eq!(
+                "0123456789abcdef0123456789abcdef01234567:secret.txt:test:2",
+                finding.get("Fingerprint").unwrap()
+            );
+            assert!(String::from_utf8_lossy(&res.stderr).contains("defaulted"));
+
+            let res = run_apply(
+                Path::new("tests/testdata/allowlist.toml"),
+                Path::new(report_path),
+                "json",
+            )?;
+            assert_eq!(String::from_utf8_lossy(&res.stdout), "[]\n");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn stdin_report() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .arg("--no-fail")
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--report-path", "-"])
+            .args(["--repo-name", "piped_repo"])
+            .args(["--format", "json"])
+            .stdin(File::open("tests/testdata/vanilla_report.json")?);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let report = from_slice::<Value>(&res.stdout)?;
+        assert_eq!(report.as_array().map(Vec::len), Some(1));
+        assert!(String::from_utf8_lossy(&res.stderr).contains("confirmed in piped_repo"));
+
+        // Repository name can't be inferred from stdin.
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--report-path", "-"])
+            .stdin(File::open("tests/testdata/vanilla_report.json")?);
+        assert!(!cmd.output()?.status.success());
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested_reports_dir() -> Result<()> {
+        let reports_dir = tempdir()?;
+        for repo in ["org1/repo1", "org1/repo2", "org2/repo1"] {
+            let path = reports_dir.path().join(format!("{repo}.json"));
+            create_dir_all(path.parent().unwrap())?;
+            copy("tests/testdata/vanilla_report.json", path)?;
+        }
+
+        let review = |option: &[&str]| -> Result<HashSet<String>> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("review")
+                .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+                .args(["--reports-dir-path", reports_dir.path().to_str().unwrap()])
+                .args(["--mode", "json"])
+                .args(option);
+            let res = cmd.output()?;
+            assert!(res.status.success());
+            let results = from_slice::<Value>(&res.stdout)?;
+            Ok(results
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|r| r.get("repo_name").and_then(Value::as_str))
+                .map(ToOwned::to_owned)
+                .collect())
+        };
+
+        let to_set = |names: &[&str]| names.iter().map(|&n| n.to_owned()).collect::<HashSet<_>>();
+        assert_eq!(
+            review(&[])?,
+            to_set(&["org1/repo1", "org1/repo2", "org2/repo1"])
+        );
+        assert_eq!(
+            review(&["--select-repos", "org1"])?,
+            to_set(&["org1/repo1", "org1/repo2"])
+        );
+        assert_eq!(
+            review(&["--skip-repos", "org1/repo2"])?,
+            to_set(&["org1/repo1", "org2/repo1"])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn github_summary() -> Result<()> {
+        let summary_dir = tempdir()?;
+        let summary_path = summary_dir.path().join("summary.md");
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .arg("--no-fail")
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--report-path", "tests/testdata/vanilla_report.json"])
+            .args(["--format", "github-summary"])
+            .args(["--guide", "See the guide."])
+            .env("GITHUB_STEP_SUMMARY", &summary_path);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        assert!(String::from_utf8_lossy(&res.stdout).starts_with("::warning file=secret.txt,"));
+
+        let summary = read_to_string(&summary_path)?;
+        assert!(summary.contains("## Secrets detected in vanilla_report"));
+        assert!(summary.contains("| test    | 1         |"), "{summary}");
+        assert!(summary.contains(
+            "[secret.txt](https://github.com/example/test_repo/blob/0123456789abcdef0123456789abcdef01234567/secret.txt#L2)"
+        ));
+        assert!(summary.ends_with("See the guide.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn gitlab_format() -> Result<()> {
+        let res = run_apply(
+            Path::new("tests/testdata/empty_allowlist.toml"),
+            Path::new("tests/testdata/vanilla_report.json"),
+            "gitlab",
+        )?;
+        let report = from_slice::<Value>(&res.stdout)?;
+        let vulnerabilities = report
+            .get("vulnerabilities")
+            .and_then(Value::as_array)
+            .unwrap();
+        assert_eq!(vulnerabilities.len(), 1);
+        let vulnerability = vulnerabilities.first().unwrap();
+        assert_eq!(
+            vulnerability.pointer("/location/file"),
+            Some(&Value::from("secret.txt"))
+        );
+        assert_eq!(
+            vulnerability.pointer("/location/start_line"),
+            Some(&Value::from(2))
+        );
+        assert_eq!(
+            vulnerability.pointer("/location/commit/sha"),
+            Some(&Value::from("0123456789abcdef0123456789abcdef01234567"))
+        );
+        assert_eq!(
+            vulnerability.pointer("/identifiers/0/value"),
+            Some(&Value::from("test"))
+        );
+        assert_eq!(
+            report.pointer("/scan/type"),
+            Some(&Value::from("secret_detection"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn junit_format() -> Result<()> {
+        let res = run_apply(
+            Path::new("tests/testdata/empty_allowlist.toml"),
+            Path::new("tests/testdata/vanilla_report.json"),
+            "junit",
+        )?;
+        let xml = String::from_utf8(res.stdout)?;
+        assert!(xml.contains(r#"<testsuite name="test" tests="1" failures="1" skipped="0">"#));
+        assert!(xml.contains(r#"<testcase name="secret.txt:2" classname="vanilla_report">"#));
+
+        let reports_dir = tempdir()?;
+        copy(
+            "tests/testdata/vanilla_report.json",
+            reports_dir.path().join("repo.json"),
+        )?;
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("review")
+            .args(["--config-path", "tests/testdata/allowlist.toml"])
+            .args(["--reports-dir-path", reports_dir.path().to_str().unwrap()])
+            .args(["--mode", "junit"])
+            .arg("--include-allowed");
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let xml = String::from_utf8(res.stdout)?;
+        assert!(xml.contains(r#"<testsuite name="test" tests="1" failures="0" skipped="1">"#));
+        assert!(xml.contains("<skipped message="), "{xml}");
+
+        // Allowlist filters apply as in other detail modes.
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("review")
+            .args(["--config-path", "tests/testdata/allowlist.toml"])
+            .args(["--reports-dir-path", reports_dir.path().to_str().unwrap()])
+            .args(["--mode", "junit"])
+            .arg("--include-allowed")
+            .args(["--skip-allowlists", "test-secret"]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let xml = String::from_utf8(res.stdout)?;
+        assert!(!xml.contains("<skipped message="), "{xml}");
+        Ok(())
+    }
+
+    #[test]
+    fn rdjson_formats() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .arg("--no-fail")
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--report-path", "tests/testdata/vanilla_report.json"])
+            .args(["--format", "rdjson"])
+            .args(["--rule-severity", "test=error"])
+            .args(["--guide", "https://example.com/guide"]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let report = from_slice::<Value>(&res.stdout)?;
+        let diagnostic = report.pointer("/diagnostics/0").unwrap();
+        assert_eq!(diagnostic.get("severity"), Some(&Value::from("ERROR")));
+        assert_eq!(
+            diagnostic.pointer("/code/value"),
+            Some(&Value::from("test"))
+        );
+        assert_eq!(
+            diagnostic.pointer("/code/url"),
+            Some(&Value::from("https://example.com/guide"))
+        );
+        assert_eq!(
+            diagnostic.pointer("/location/range/end/column"),
+            Some(&Value::from(21))
+        );
+
+        let res = run_apply(
+            Path::new("tests/testdata/empty_allowlist.toml"),
+            Path::new("tests/testdata/vanilla_report.json"),
+            "rdjsonl",
+        )?;
+        let stdout = String::from_utf8(res.stdout)?;
+        assert_eq!(stdout.lines().count(), 1);
+        let diagnostic = serde_json::from_str::<Value>(stdout.trim_end())?;
+        assert_eq!(diagnostic.get("severity"), Some(&Value::from("WARNING")));
+        assert_eq!(
+            diagnostic.pointer("/location/path"),
+            Some(&Value::from("secret.txt"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn redact() -> Result<()> {
+        for format in ["json", "github", "sarif", "junit"] {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("apply")
+                .arg("--no-fail")
+                .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+                .args(["--report-path", "tests/testdata/vanilla_report.json"])
+                .args(["--format", format])
+                .args(["--redact", "keep:2"]);
+            let res = cmd.output()?;
+            assert!(res.status.success());
+            let stdout = String::from_utf8(res.stdout)?;
+            assert!(!stdout.contains("deadbeef"), "{format}: {stdout}");
+            assert!(stdout.contains("de***ef"), "{format}: {stdout}");
+        }
+
+        // Global option can be placed before the subcommand.
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.args(["--redact", "full", "review"])
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--reports-dir-path", "tests/testdata/vanilla_report.json"])
+            .args(["--mode", "confirmed"]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let stdout = String::from_utf8(res.stdout)?;
+        assert!(!stdout.contains("deadbeef"), "{stdout}");
+        assert!(stdout.contains("REDACTED"), "{stdout}");
+
+        // Hashes are keyed when the key is given.
+        for (key_args, prefix) in [
+            (&[][..], "sha256:"),
+            (&["--secret-hash-key", "test-key"][..], "hmac-sha256:"),
+        ] {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.args(["--redact", "hash", "review"])
+                .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+                .args(["--reports-dir-path", "tests/testdata/vanilla_report.json"])
+                .args(["--mode", "json"])
+                .args(key_args);
+            let res = cmd.output()?;
+            assert!(res.status.success());
+            let stdout = String::from_utf8(res.stdout)?;
+            let results = serde_json::from_str::<Value>(&stdout)?;
+            let secret = results
+                .pointer("/0/confirmed/0/Secret")
+                .and_then(Value::as_str)
+                .unwrap();
+            assert!(secret.starts_with(prefix), "{stdout}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn github_annotations() -> Result<()> {
+        let report = read_to_string("tests/testdata/vanilla_report.json")?;
+        let mut findings = serde_json::from_str::<Vec<Value>>(&report)?;
+        let mut finding = findings.pop().unwrap();
+        let object = finding.as_object_mut().unwrap();
+        object.insert("File".to_owned(), Value::from("dir,1/100%:secret.txt"));
+        // Secrets can't inject workflow commands.
+        object.insert("Secret".to_owned(), Value::from("dead\n::add-mask::beef"));
+        let report_dir = tempdir()?;
+        let report_path = report_dir.path().join("report.json");
+        serde_json::to_writer(File::create(&report_path)?, &vec![finding.clone(); 12])?;
+
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .arg("--no-fail")
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--report-path", report_path.to_str().unwrap()])
+            .args(["--format", "github"])
+            .args(["--rule-severity", "test=error"]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let stdout = String::from_utf8(res.stdout)?;
+        let annotations = stdout
+            .lines()
+            .filter(|line| line.starts_with("::"))
+            .collect::<Vec<_>>();
+        assert_eq!(annotations.len(), 10, "{stdout}");
+        assert_eq!(
+            annotations.first().copied(),
+            "::error file=dir%2C1/100%25%3Asecret.txt,line=2,endLine=2,col=1,endColumn=21,\
+             title=Secrets detected::`dead%0A::add-mask::beef` is considered as secret value."
+                .into()
+        );
+        assert!(
+            annotations
+                .last()
+                .is_some_and(|line| line.contains("3 more findings are not annotated")),
+            "{stdout}"
+        );
+        assert!(stdout.contains(
+            "error: dir,1/100%25:secret.txt:2: `dead%0A::add-mask::beef` is considered as secret value.\n"
+        ));
+
+        // Findings under the limit are all annotated, as warnings by default.
+        serde_json::to_writer(File::create(&report_path)?, &vec![finding; 2])?;
+        let res = run_apply(
+            Path::new("tests/testdata/empty_allowlist.toml"),
+            &report_path,
+            "github",
+        )?;
+        let stdout = String::from_utf8(res.stdout)?;
+        assert_eq!(stdout.lines().count(), 2, "{stdout}");
+        assert!(stdout.lines().all(|line| line.starts_with("::warning ")));
+        Ok(())
+    }
+
+    #[test]
+    fn baseline() -> Result<()> {
+        let baseline_dir = tempdir()?;
+        let baseline_path = baseline_dir.path().join("baseline.json");
+        let run = |report_path: &Path, args: &[&str]| -> Result<Output> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("apply")
+                .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+                .args(["--report-path", report_path.to_str().unwrap()])
+                .args(["--format", "json"])
+                .args(["--baseline", baseline_path.to_str().unwrap()])
+                .args(args);
+            Ok(cmd.output()?)
+        };
+
+        let vanilla_report = Path::new("tests/testdata/vanilla_report.json");
+        let res = run(vanilla_report, &["--update-baseline"])?;
+        assert!(res.status.success());
+        let baseline = serde_json::from_str::<Value>(&read_to_string(&baseline_path)?)?;
+        assert_eq!(baseline.as_array().map(Vec::len), Some(1));
+
+        // Known findings are not reported, so apply succeeds without `--no-fail`.
+        let res = run(vanilla_report, &[])?;
+        assert!(res.status.success());
+        assert_eq!(from_slice::<Value>(&res.stdout)?, Value::Array(Vec::new()));
+
+        // Shifted findings have other fingerprints.
+        let report = read_to_string(vanilla_report)?;
+        let mut findings = serde_json::from_str::<Vec<Value>>(&report)?;
+        let object = findings.first_mut().unwrap().as_object_mut().unwrap();
+        object.insert("StartLine".to_owned(), Value::from(3));
+        object.insert("EndLine".to_owned(), Value::from(3));
+        object.insert(
+            "Fingerprint".to_owned(),
+            Value::from("0123456789abcdef0123456789abcdef01234567:secret.txt:test:3"),
+        );
+        let report_path = baseline_dir.path().join("shifted.json");
+        serde_json::to_writer(File::create(&report_path)?, &findings)?;
+        let res = run(&report_path, &[])?;
+        assert!(!res.status.success());
+        let res = run(&report_path, &["--baseline-match", "secret"])?;
+        assert!(res.status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn include_allowed() -> Result<()> {
+        let run = |format: &str| -> Result<Value> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("apply")
+                .args(["--config-path", "tests/testdata/allowlist.toml"])
+                .args(["--report-path", "tests/testdata/vanilla_report.json"])
+                .args(["--format", format])
+                .arg("--include-allowed");
+            let res = cmd.output()?;
+            assert!(res.status.success());
+            Ok(from_slice::<Value>(&res.stdout)?)
+        };
+
+        let report = run("json")?;
+        assert_eq!(
+            report
+                .get("confirmed")
+                .and_then(Value::as_array)
+                .map(Vec::len),
+            Some(0)
+        );
+        assert_eq!(
+            report.pointer("/allowed/0/allow_rule_id"),
+            Some(&Value::from("test-secret"))
+        );
+
+        let report = run("sarif")?;
+        let result = report.pointer("/runs/0/results/0").unwrap();
+        assert_eq!(result.get("ruleId"), Some(&Value::from("test")));
+        assert_eq!(
+            result.pointer("/suppressions/0/kind"),
+            Some(&Value::from("external"))
+        );
+        assert_eq!(
+            result.pointer("/suppressions/0/justification"),
+            Some(&Value::from("test-secret: Test secret pattern"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn csv_format() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .arg("--no-fail")
+            .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+            .args(["--report-path", "tests/testdata/vanilla_report.csv"])
+            .args(["--format", "csv"])
+            .args(["--csv-columns", "status,rule_id,file,start_line,secret"]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        assert_eq!(
+            String::from_utf8(res.stdout)?,
+            "status,rule_id,file,start_line,secret\nconfirmed,test,secret.txt,2,deadbeef\n"
+        );
+
+        let reports_dir = tempdir()?;
+        copy(
+            "tests/testdata/vanilla_report.json",
+            reports_dir.path().join("repo.json"),
+        )?;
+        let review = |mode: &str| -> Result<String> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("review")
+                .args(["--config-path", "tests/testdata/allowlist.toml"])
+                .args(["--reports-dir-path", reports_dir.path().to_str().unwrap()])
+                .args(["--mode", mode])
+                .args(["--format", "csv"]);
+            let res = cmd.output()?;
+            assert!(res.status.success(), "{mode}");
+            Ok(String::from_utf8(res.stdout)?)
+        };
+        let stdout = review("allowed")?;
+        assert!(
+            stdout.starts_with("repo,status,allowlist,rule_id,file,start_line,end_line,secret,"),
+            "{stdout}"
+        );
+        assert!(
+            stdout.contains("repo,allowed,test-secret,test,secret.txt,2,2,deadbeef,"),
+            "{stdout}"
+        );
+        assert_eq!(review("confirmed")?.lines().count(), 1);
+        assert_eq!(
+            review("summary")?,
+            indoc! {"
+                kind,name,total,allowed,confirmed
+                total,,1,1,0
+                rule,test,1,1,0
+                allowlist,test-secret,1,1,0
+            "}
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn severities() -> Result<()> {
+        let config_dir = tempdir()?;
+        let config_path = config_dir.path().join("config.toml");
+        let mut config = File::create(&config_path)?;
+        write!(
+            config,
+            "{}",
+            indoc! {r#"
+                [extensions]
+                allowlists = []
+
+                [extensions.severities]
+                rules = { test = "info" }
+            "#}
+        )?;
+        let run = |args: &[&str]| -> Result<Output> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("apply")
+                .args(["--config-path", config_path.to_str().unwrap()])
+                .args(["--report-path", "tests/testdata/vanilla_report.json"])
+                .args(args);
+            Ok(cmd.output()?)
+        };
+
+        let res = run(&["--format", "sarif", "--no-fail"])?;
+        let report = from_slice::<Value>(&res.stdout)?;
+        assert_eq!(
+            report.pointer("/runs/0/results/0/level"),
+            Some(&Value::from("note"))
+        );
+
+        let res = run(&["--format", "json"])?;
+        assert!(!res.status.success());
+        let res = run(&["--format", "json", "--fail-on", "warning"])?;
+        assert!(res.status.success());
+        let res = run(&[
+            "--format",
+            "json",
+            "--fail-on",
+            "warning",
+            "--rule-severity",
+            "test=error",
+        ])?;
+        assert!(!res.status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn html_mode() -> Result<()> {
+        let reports_dir = tempdir()?;
+        copy(
+            "tests/testdata/vanilla_report.json",
+            reports_dir.path().join("repo.json"),
+        )?;
+        let output_dir = tempdir()?;
+        let output_path = output_dir.path().join("review.html");
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("review")
+            .args(["--config-path", "tests/testdata/allowlist.toml"])
+            .args(["--reports-dir-path", reports_dir.path().to_str().unwrap()])
+            .args(["--select-repos", "repo"])
+            .args(["--mode", "html"])
+            .args(["--output", output_path.to_str().unwrap()]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let html = read_to_string(&output_path)?;
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(r#"data-status="allowed" data-repo="repo" data-rule="test""#));
+        assert!(html.contains("<pre>secret_key = deadbeef</pre>"));
+
+        // Rule filters apply as in other detail modes.
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("review")
+            .args(["--config-path", "tests/testdata/allowlist.toml"])
+            .args(["--reports-dir-path", reports_dir.path().to_str().unwrap()])
+            .args(["--select-repos", "repo"])
+            .args(["--mode", "html"])
+            .args(["--skip-allowlists", "test-secret"])
+            .args(["--output", output_path.to_str().unwrap()]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let html = read_to_string(&output_path)?;
+        assert!(!html.contains(r#"data-status="allowed""#));
+        Ok(())
+    }
+
+    #[test]
+    fn sarif_rules() -> Result<()> {
+        let config_dir = tempdir()?;
+        let config_path = config_dir.path().join("config.toml");
+        write(&config_path, "[extensions]\nallowlists = []\n")?;
+        let gitleaks_config_path = config_dir.path().join("gitleaks.toml");
+        let mut gitleaks_config = File::create(&gitleaks_config_path)?;
+        write!(
+            gitleaks_config,
+            "{}",
+            indoc! {r#"
+                [[rules]]
+                id = "test"
+                description = "Test secret"
+                regex = 'deadbeef'
+                keywords = ["deadbeef"]
+                tags = ["test-tag"]
+            "#}
+        )?;
+        let mut cmd = Command::cargo_bin("gls")?;
+        cmd.arg("apply")
+            .arg("--no-fail")
+            .args(["--config-path", config_path.to_str().unwrap()])
+            .args(["--report-path", "tests/testdata/vanilla_report.json"])
+            .args(["--format", "sarif"])
+            .args([
+                "--gitleaks-config-path",
+                gitleaks_config_path.to_str().unwrap(),
+            ])
+            .args(["--guide", "Rotate the secret."]);
+        let res = cmd.output()?;
+        assert!(res.status.success());
+        let report = from_slice::<Value>(&res.stdout)?;
+        let rule = report
+            .pointer("/runs/0/tool/driver/rules/0")
+            .with_context(|| "No rule")?;
+        assert_eq!(
+            rule.pointer("/shortDescription/text"),
+            Some(&Value::from("Test secret"))
+        );
+        assert_eq!(
+            rule.pointer("/fullDescription/text"),
+            Some(&Value::from("Test secret\n\nKeywords: deadbeef."))
+        );
+        assert_eq!(
+            rule.pointer("/help/text"),
+            Some(&Value::from("Test secret\n\nRotate the secret."))
+        );
+        assert_eq!(
+            rule.pointer("/properties/tags/0"),
+            Some(&Value::from("test-tag"))
+        );
+        assert_eq!(
+            rule.pointer("/defaultConfiguration/level"),
+            Some(&Value::from("warning"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sarif_fingerprints() -> Result<()> {
+        let report_dir = tempdir()?;
+        let report = read_to_string("tests/testdata/vanilla_report.json")?;
+        let mut findings = serde_json::from_str::<Vec<Value>>(&report)?;
+        let finding = findings.first().unwrap().clone();
+        // Same secret on another line, and another rule sorted before `test`.
+        let mut shifted = finding.clone();
+        let object = shifted.as_object_mut().unwrap();
+        object.insert("StartLine".to_owned(), Value::from(3));
+        object.insert("EndLine".to_owned(), Value::from(3));
+        object.insert(
+            "Fingerprint".to_owned(),
+            Value::from("0123456789abcdef0123456789abcdef01234567:secret.txt:test:3"),
+        );
+        let mut another = finding;
+        let object = another.as_object_mut().unwrap();
+        object.insert("RuleID".to_owned(), Value::from("another"));
+        findings.extend([shifted, another]);
+        let report_path = report_dir.path().join("report.json");
+        serde_json::to_writer(File::create(&report_path)?, &findings)?;
+
+        let run = |args: &[&str]| -> Result<Vec<u8>> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.args(["--redact", "full", "apply"])
+                .arg("--no-fail")
+                .args(["--config-path", "tests/testdata/empty_allowlist.toml"])
+                .args(["--report-path", report_path.to_str().unwrap()])
+                .args(["--format", "sarif"])
+                .args(args);
+            let res = cmd.output()?;
+            assert!(res.status.success());
+            Ok(res.stdout)
+        };
+        let key_args = ["--secret-hash-key", "test-key"];
+        let stdout = run(&key_args)?;
+        assert_eq!(stdout, run(&key_args)?);
+
+        let report = from_slice::<Value>(&stdout)?;
+        let rule_ids = report
+            .pointer("/runs/0/tool/driver/rules")
+            .and_then(Value::as_array)
+            .with_context(|| "No rules")?
+            .iter()
+            .filter_map(|rule| rule.get("id").and_then(Value::as_str))
+            .collect::<Vec<_>>();
+        assert_eq!(rule_ids, ["another", "test"]);
+        let result = |index: usize, pointer: &str| {
+            report
+                .pointer(&format!("/runs/0/results/{index}{pointer}"))
+                .cloned()
+                .unwrap_or_default()
+        };
+        assert_eq!(result(0, "/ruleIndex"), Value::from(1));
+        assert_eq!(result(2, "/ruleIndex"), Value::from(0));
+        assert_eq!(
+            result(0, "/partialFingerprints/gitleaksFingerprint~1v1"),
+            Value::from("0123456789abcdef0123456789abcdef01234567:secret.txt:test:2")
+        );
+        let secret_hash = "/partialFingerprints/secretHash~1v1";
+        assert_eq!(result(0, secret_hash).as_str().map(str::len), Some(64));
+        assert_eq!(result(0, secret_hash), result(1, secret_hash));
+        assert_ne!(result(0, secret_hash), result(2, secret_hash));
+        assert_eq!(
+            result(0, "/properties/email"),
+            Value::from("test@example.com")
+        );
+
+        // Secret hashes of redacted outputs need the key.
+        let report = from_slice::<Value>(&run(&[])?)?;
+        let fingerprints = report
+            .pointer("/runs/0/results/0/partialFingerprints")
+            .and_then(Value::as_object)
+            .with_context(|| "No partialFingerprints")?;
+        assert!(fingerprints.contains_key("gitleaksFingerprint/v1"));
+        assert!(!fingerprints.contains_key("secretHash/v1"));
+        Ok(())
+    }
+
+    #[test]
+    fn sarif_rule_levels() -> Result<()> {
+        let dir = tempdir()?;
+        let config_path = dir.path().join("config.toml");
+        write(
+            &config_path,
+            "[extensions]\nallowlists = []\n[extensions.severities]\ntags = { cloud = \"error\" }\n",
+        )?;
+        let report = read_to_string("tests/testdata/vanilla_report.json")?;
+        let mut findings = serde_json::from_str::<Vec<Value>>(&report)?;
+        findings
+            .first_mut()
+            .unwrap()
+            .as_object_mut()
+            .unwrap()
+            .insert("Tags".to_owned(), Value::from(vec!["cloud"]));
+        let report_path = dir.path().join("report.json");
+        serde_json::to_writer(File::create(&report_path)?, &findings)?;
+
+        // Without the gitleaks config, rules have the tags of their findings.
+        let res = run_apply(&config_path, &report_path, "sarif")?;
+        let report = from_slice::<Value>(&res.stdout)?;
+        assert_eq!(
+            report.pointer("/runs/0/results/0/level"),
+            Some(&Value::from("error"))
+        );
+        assert_eq!(
+            report.pointer("/runs/0/tool/driver/rules/0/defaultConfiguration/level"),
+            Some(&Value::from("error"))
+        );
+        assert_eq!(
+            report.pointer("/runs/0/tool/driver/rules/0/properties/tags/0"),
+            Some(&Value::from("cloud"))
+        );
+        Ok(())
+    }
 }
@@ -92,4 +92,75 @@ mod format {
             Ok(())
         }
     }
+
+    mod convert {
+        use std::{fs::read_to_string, process::Command};
+
+        use anyhow::Result;
+        use assert_cmd::prelude::*;
+        use tempfile::tempdir;
+
+        #[test]
+        fn toml_to_json_and_yaml() -> Result<()> {
+            let temp = tempdir()?;
+            let json_path = temp.path().join("actual.json");
+            let yaml_path = temp.path().join("actual.yaml");
+
+            for path in [&json_path, &yaml_path] {
+                let mut cmd = Command::cargo_bin("gls")?;
+                cmd.arg("format")
+                    .args(["--source", "tests/testdata/allowlist.toml"])
+                    .args(["--output", path.to_str().unwrap()]);
+                cmd.assert().success();
+            }
+
+            let expected = include_str!("format/format_json_expected.json");
+            assert_eq!(expected, read_to_string(json_path)?);
+            let expected = include_str!("format/format_yaml_expected.yaml");
+            assert_eq!(expected, read_to_string(yaml_path)?);
+
+            Ok(())
+        }
+
+        #[test]
+        fn yaml_to_toml() -> Result<()> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("format")
+                .args(["--source", "tests/format/format_yaml_expected.yaml"])
+                .args(["--output-format", "toml"]);
+
+            let output = cmd.assert().success().get_output().stdout.clone();
+            let expected = read_to_string("tests/testdata/allowlist.toml")?;
+            assert_eq!(String::from_utf8(output)?.trim_end(), expected.trim_end());
+
+            Ok(())
+        }
+    }
+
+    mod schema {
+        use std::process::Command;
+
+        use anyhow::Result;
+        use assert_cmd::prelude::*;
+        use serde_json::{Value, from_slice};
+
+        #[test]
+        fn basic() -> Result<()> {
+            let mut cmd = Command::cargo_bin("gls")?;
+            cmd.arg("schema");
+
+            let output = cmd.assert().success().get_output().stdout.clone();
+            let schema = from_slice::<Value>(&output)?;
+            assert_eq!(schema.get("title").unwrap(), "ConfigRoot");
+            for name in ["Extensions", "Allowlist", "RegexTarget"] {
+                let description = schema.pointer(&format!("/$defs/{name}/description"));
+                assert!(description.is_some_and(Value::is_string), "{name}");
+            }
+            let description =
+                schema.pointer("/$defs/Allowlist/properties/targetRuleIds/description");
+            assert!(description.is_some_and(Value::is_string));
+
+            Ok(())
+        }
+    }
 }